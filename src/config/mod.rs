@@ -7,11 +7,31 @@ use tracing::*;
 pub mod ty;
 
 static mut CONFIG: Option<Config> = None;
+static PATH: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 
 pub fn get() -> &'static Config {
     unsafe { CONFIG.as_ref().expect("Config uninitialized") }
 }
 
+/// Loads the default configuration, for tests that depend on the loaded one.
+/// Every player is listed in the tab list, so that tab list updates can be tested.
+#[cfg(test)]
+pub fn init_test() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let config = Figment::from(Toml::string(include_str!("default.toml")))
+            .merge(Toml::string("tab_list.players = \"all\""))
+            .extract()
+            .expect("Default configuration is invalid");
+        unsafe { CONFIG = Some(config) };
+    });
+}
+
+/// Returns the path of the loaded configuration file.
+pub fn path() -> &'static str {
+    PATH.get().expect("Config uninitialized")
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Config {
     /// IP address to bind to
@@ -40,6 +60,51 @@ pub struct Config {
     pub allow_flight: bool,
     /// Where the player should spawn
     pub spawn_location: ty::Location,
+    /// Maximum view distance (in chunks), lowered per player to the view distance set in their client
+    #[serde(default = "default_distance")]
+    pub view_distance: u32,
+    /// Simulation distance (in chunks) sent to players
+    #[serde(default = "default_distance")]
+    pub simulation_distance: u32,
+    /// Path to registry data replacing or extending the built-in data (biomes, dimension types, ...)
+    pub registry_data: Option<String>,
+    /// The world that is sent to players
+    #[serde(default)]
+    pub world: ty::WorldConfig,
+    /// Shows the players in the limbo to each other, instead of every player being alone
+    pub other_players: Option<ty::OtherPlayersConfig>,
+    /// Sends players back to the spawn location when they fall or wander off
+    pub fall_protection: Option<ty::FallProtection>,
+    /// Warns idle players and removes them from the limbo
+    pub afk: Option<ty::AfkConfig>,
+    /// Limits how long players may stay in the limbo
+    pub session_limit: Option<ty::SessionLimit>,
+    /// Makes the limbo a waiting room, releasing players in order of priority and arrival
+    pub queue: Option<ty::QueueConfig>,
+    /// Watches the backend servers of the proxy, sending players back once their server is up again
+    pub auto_return: Option<ty::AutoReturnConfig>,
+    /// How chat messages from players are handled
+    #[serde(default)]
+    pub chat: ty::ChatConfig,
+    /// Commands available to players
+    #[serde(default)]
+    pub commands: ty::CommandsConfig,
+    /// An optional title shown to players when they join
+    pub title: Option<ty::TitleConfig>,
+    /// An optional message shown above the hotbar of players
+    pub action_bar: Option<ty::ActionBarConfig>,
+    /// An optional boss bar shown at the top of the screen of players
+    pub boss_bar: Option<ty::BossBarConfig>,
+    /// The tab list (player list) of players
+    #[serde(default)]
+    pub tab_list: ty::TabListConfig,
+    /// An optional scoreboard shown at the right side of the screen of players
+    pub sidebar: Option<ty::SidebarConfig>,
+}
+
+/// The default view and simulation distance (in chunks).
+fn default_distance() -> u32 {
+    8
 }
 
 impl Config {
@@ -50,8 +115,29 @@ impl Config {
             info!("Configuration file not found, creating default");
             std::fs::write(path, include_str!("default.toml"))?;
         }
-        let config = Figment::from(Toml::file_exact(path)).extract()?;
+        let config = Self::read(path)?;
+        let _ = PATH.set(path.into());
         unsafe { CONFIG = Some(config) };
         Ok(get())
     }
+
+    /// Reads a configuration file, without replacing the loaded configuration.
+    pub fn read(path: &str) -> anyhow::Result<Config> {
+        let config: Config = Figment::from(Toml::file_exact(path)).extract()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the settings that depend on each other.
+    fn validate(&self) -> anyhow::Result<()> {
+        let spawn = self.spawn_location;
+        if let Some(border) = &self.world.border {
+            // Players outside the border are sent back to the spawn location, over and over
+            anyhow::ensure!(
+                border.contains_throughout(spawn.x(), spawn.z()),
+                "The world border must contain the spawn location"
+            );
+        }
+        Ok(())
+    }
 }
@@ -1,4 +1,28 @@
+mod action;
+pub use action::*;
+mod afk;
+pub use afk::*;
+mod backends;
+pub use backends::*;
+mod border;
+pub use border::*;
+mod chat;
+pub use chat::*;
+mod commands;
+pub use commands::*;
+mod display;
+pub use display::*;
 mod gamemode;
 pub use gamemode::*;
+mod limit;
+pub use limit::*;
 mod location;
 pub use location::*;
+mod protection;
+pub use protection::*;
+mod queue;
+pub use queue::*;
+mod visibility;
+pub use visibility::*;
+mod world;
+pub use world::*;
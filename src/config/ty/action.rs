@@ -0,0 +1,15 @@
+/// What happens to a player that has to leave the limbo.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExitAction {
+    /// The player is disconnected.
+    Disconnect {
+        /// The reason shown to the player
+        message: String,
+    },
+    /// The player is sent to another server of the proxy, using the BungeeCord plugin channel.
+    Connect {
+        /// Name of the server (as configured in the proxy)
+        server: String,
+    },
+}
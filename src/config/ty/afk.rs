@@ -0,0 +1,16 @@
+/// Warns idle players and removes them from the limbo.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AfkConfig {
+    /// Time (in seconds) without activity after which players are warned
+    pub warn_after: u64,
+    /// Time (in seconds) between two warnings
+    pub warning_interval: u64,
+    /// Chat message warning players, where `{remaining}` is replaced with the time until `action`
+    pub warning_message: String,
+    /// Time (in seconds) without activity after which `action` happens
+    pub kick_after: u64,
+    /// What happens to idle players
+    pub action: super::ExitAction,
+    /// Names or UUIDs of players that are never considered idle
+    pub bypass: Vec<String>,
+}
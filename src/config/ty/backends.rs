@@ -0,0 +1,25 @@
+/// Watches the backend servers of the proxy, sending players back once their server is up again.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AutoReturnConfig {
+    /// The backend servers that are watched, the first one being used for players
+    /// whose server is unknown
+    pub backends: Vec<Backend>,
+    /// Time (in seconds) between two status requests to a backend
+    pub poll_interval: u64,
+    /// Time (in seconds) a backend has to answer a status request before it is considered down
+    pub timeout: u64,
+    /// Name of an optional game profile property (forwarded by the proxy) holding the name
+    /// of the server the player came from
+    pub server_property: Option<String>,
+    /// Chat message sent to players before they are sent back, where `{server}` is replaced
+    pub message: String,
+}
+
+/// A backend server of the proxy.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Backend {
+    /// Name of the server (as configured in the proxy)
+    pub name: String,
+    /// Address (`host:port`) the server is polled at
+    pub address: String,
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+/// A world border, optionally shrinking (or growing) once a player joins.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorldBorder {
+    /// X coordinate of the center of the border
+    pub center_x: f64,
+    /// Z coordinate of the center of the border
+    pub center_z: f64,
+    /// Diameter of the border in blocks
+    pub diameter: f64,
+    /// Distance (in blocks) from the border at which the screen turns red
+    pub warning_distance: u32,
+    /// Time (in seconds) before a shrinking border reaches the player at which the screen turns red
+    pub warning_time: u32,
+    /// An optional animation from another diameter to `diameter`
+    pub shrink: Option<BorderShrink>,
+}
+
+/// An animation of the world border's diameter, starting when a player joins.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BorderShrink {
+    /// Diameter of the border in blocks when the player joins
+    pub from: f64,
+    /// Time (in seconds) it takes to reach the final diameter
+    pub duration: u64,
+}
+
+impl WorldBorder {
+    /// Returns the diameter of the border the given time after joining.
+    pub fn diameter_at(&self, elapsed: Duration) -> f64 {
+        let Some(shrink) = &self.shrink else {
+            return self.diameter;
+        };
+        let duration = Duration::from_secs(shrink.duration);
+        if elapsed >= duration {
+            return self.diameter;
+        }
+        let progress = elapsed.as_secs_f64() / duration.as_secs_f64();
+        shrink.from + (self.diameter - shrink.from) * progress
+    }
+
+    /// Returns whether the given coordinates are inside the border the given time after joining.
+    pub fn contains(&self, x: f64, z: f64, elapsed: Duration) -> bool {
+        let radius = self.diameter_at(elapsed) / 2.0;
+        (x - self.center_x).abs() <= radius && (z - self.center_z).abs() <= radius
+    }
+
+    /// Returns whether the given coordinates are inside the border both when a player joins
+    /// and once any animation is over (and so at any time in between).
+    pub fn contains_throughout(&self, x: f64, z: f64) -> bool {
+        self.contains(x, z, Duration::ZERO) && self.contains(x, z, Duration::MAX)
+    }
+}
@@ -0,0 +1,45 @@
+/// How chat messages from players are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatMode {
+    /// Chat is disabled, players are told so when they try to chat.
+    #[default]
+    Disabled,
+    /// Messages are shown to every player in the limbo.
+    Local,
+    /// Messages are only shown to the player who sent them.
+    Echo,
+}
+
+/// Chat configuration.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChatConfig {
+    /// How chat messages from players are handled
+    pub mode: ChatMode,
+    /// Message sent to players who try to chat while chat is disabled
+    pub disabled_message: String,
+    /// Format of chat messages, where `{player}` and `{message}` are replaced
+    pub format: String,
+    /// Maximum length of a chat message in characters
+    pub max_length: usize,
+    /// Message sent to players whose message is too long
+    pub too_long_message: String,
+    /// Minimum time (in milliseconds) between two chat messages of a player
+    pub cooldown: u64,
+    /// Message sent to players who chat too fast
+    pub cooldown_message: String,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            mode: ChatMode::default(),
+            disabled_message: "§cChat is disabled here.".into(),
+            format: "<{player}> {message}".into(),
+            max_length: 256,
+            too_long_message: "§cYour message is too long.".into(),
+            cooldown: 1000,
+            cooldown_message: "§cPlease wait before sending another message.".into(),
+        }
+    }
+}
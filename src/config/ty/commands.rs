@@ -0,0 +1,92 @@
+/// Settings shared by all built-in commands.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandConfig {
+    /// Other names the command can be used with
+    pub aliases: Vec<String>,
+    /// Description of the command shown by `/help`
+    pub description: String,
+}
+
+/// Commands available to players.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandsConfig {
+    /// Message sent to players who use a command that does not exist
+    pub unknown_message: String,
+    /// `/spawn`, teleporting the player back to the spawn location
+    pub spawn: Option<SpawnCommand>,
+    /// `/help`, listing the available commands
+    pub help: Option<HelpCommand>,
+    /// `/lobby`, sending the player to another server of the proxy
+    pub lobby: Option<LobbyCommand>,
+    /// `/ping`, showing the latency of the player
+    pub ping: Option<PingCommand>,
+}
+
+/// The `/spawn` command.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpawnCommand {
+    #[serde(flatten)]
+    pub command: CommandConfig,
+    /// An optional chat message sent after teleporting
+    pub message: Option<String>,
+}
+
+/// The `/help` command.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HelpCommand {
+    #[serde(flatten)]
+    pub command: CommandConfig,
+    /// First line of the list of commands
+    pub header: String,
+    /// Format of each command in the list, where `{command}` and `{description}` are replaced
+    pub format: String,
+}
+
+/// The `/lobby` command.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LobbyCommand {
+    #[serde(flatten)]
+    pub command: CommandConfig,
+    /// Name of the server (as configured in the proxy) the player is sent to
+    pub server: String,
+    /// An optional chat message sent before the player is sent to the server
+    pub message: Option<String>,
+}
+
+/// The `/ping` command.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PingCommand {
+    #[serde(flatten)]
+    pub command: CommandConfig,
+    /// Message showing the latency, where `{ping}` is replaced with the latency in milliseconds
+    pub message: String,
+    /// Message sent when the latency of the player has not been measured yet
+    pub unknown_message: String,
+}
+
+impl Default for CommandsConfig {
+    fn default() -> Self {
+        let command = |aliases: &[&str], description: &str| CommandConfig {
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            description: description.into(),
+        };
+        Self {
+            unknown_message: "§cUnknown command. Type /help for a list of commands.".into(),
+            spawn: Some(SpawnCommand {
+                command: command(&[], "Teleports you back to the spawn"),
+                message: None,
+            }),
+            help: Some(HelpCommand {
+                command: command(&["?"], "Shows this list of commands"),
+                header: "§6Available commands:".into(),
+                format: "§e/{command} §7- {description}".into(),
+            }),
+            lobby: None,
+            ping: Some(PingCommand {
+                command: command(&[], "Shows your latency"),
+                message: "§aYour ping is {ping}ms.".into(),
+                unknown_message: "§cYour ping has not been measured yet.".into(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+/// A title shown to players when they join.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TitleConfig {
+    /// The large text in the middle of the screen
+    pub title: String,
+    /// The smaller text below the title
+    pub subtitle: String,
+    /// Time (in ticks) the title takes to fade in
+    pub fade_in: u32,
+    /// Time (in ticks) the title stays on screen
+    pub stay: u32,
+    /// Time (in ticks) the title takes to fade out
+    pub fade_out: u32,
+}
+
+/// A message shown above the hotbar of players.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ActionBarConfig {
+    /// The message to show
+    pub message: String,
+    /// Time (in seconds) between two updates of the message. It fades out after about
+    /// 3 seconds, so a lower interval keeps it on screen while a higher one makes it repeat
+    pub interval: u64,
+}
+
+/// The color of a boss bar.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BossBarColor {
+    Pink,
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    Purple,
+    White,
+}
+
+/// How a boss bar is divided.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BossBarStyle {
+    /// A single solid bar.
+    Progress,
+    /// A bar divided into 6 segments.
+    Notched6,
+    /// A bar divided into 10 segments.
+    Notched10,
+    /// A bar divided into 12 segments.
+    Notched12,
+    /// A bar divided into 20 segments.
+    Notched20,
+}
+
+/// How full a boss bar is.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BossBarProgress {
+    /// The bar never changes.
+    Static {
+        /// How full the bar is, from 0.0 to 1.0
+        value: f32,
+    },
+    /// The bar fills up while the player is waiting.
+    Fill {
+        /// Time (in seconds) it takes to fill the bar
+        duration: u64,
+    },
+    /// The bar empties while the player is waiting, like a countdown.
+    Drain {
+        /// Time (in seconds) it takes to empty the bar
+        duration: u64,
+    },
+}
+
+/// A boss bar shown at the top of the screen of players.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BossBarConfig {
+    /// The text above the bar, where `{remaining}` is replaced with the time
+    /// until a filling or draining bar is done
+    pub text: String,
+    /// The color of the bar
+    pub color: BossBarColor,
+    /// How the bar is divided
+    pub style: BossBarStyle,
+    /// How full the bar is
+    pub progress: BossBarProgress,
+    /// Time (in seconds) between two updates of the text and progress
+    pub interval: u64,
+}
+
+impl BossBarConfig {
+    /// Returns how full the bar is and the time until it is done, the given time after joining.
+    pub fn progress_at(&self, elapsed: Duration) -> (f32, Option<Duration>) {
+        let (duration, fill) = match self.progress {
+            BossBarProgress::Static { value } => return (value.clamp(0.0, 1.0), None),
+            BossBarProgress::Fill { duration } => (Duration::from_secs(duration), true),
+            BossBarProgress::Drain { duration } => (Duration::from_secs(duration), false),
+        };
+        let done = if duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+        };
+        let progress = if fill { done } else { 1.0 - done };
+        (progress, Some(duration.saturating_sub(elapsed)))
+    }
+}
+
+/// Which players are listed in the tab list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabListPlayers {
+    /// Players only see themselves.
+    #[default]
+    SelfOnly,
+    /// Players see every player in the limbo.
+    All,
+    /// Players see nobody.
+    Nobody,
+}
+
+/// The tab list (player list) of players.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TabListConfig {
+    /// Which players are listed
+    pub players: TabListPlayers,
+    /// Text above the list of players (empty for none)
+    pub header: String,
+    /// Text below the list of players (empty for none)
+    pub footer: String,
+    /// Time (in seconds) between two updates of the header and footer
+    pub interval: u64,
+}
+
+impl Default for TabListConfig {
+    fn default() -> Self {
+        Self {
+            players: TabListPlayers::default(),
+            header: "§6Limbo\n§7{online} players online".into(),
+            footer: "§7You have been waiting for §e{waited}".into(),
+            interval: 1,
+        }
+    }
+}
+
+/// A scoreboard shown at the right side of the screen of players.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SidebarConfig {
+    /// The title above the lines
+    pub title: String,
+    /// The lines of text from top to bottom, up to 15 (any further lines are not shown)
+    pub lines: Vec<String>,
+    /// Time (in seconds) between two updates of the text, which is also updated
+    /// whenever the number of players changes
+    pub interval: u64,
+}
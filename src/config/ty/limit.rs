@@ -0,0 +1,15 @@
+/// Limits how long players may stay in the limbo.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionLimit {
+    /// Maximum time (in seconds) a player may stay, counted from when it logged in
+    pub max_session_duration: u64,
+    /// Times (in seconds) before the end at which players are warned in chat
+    pub warnings: Vec<u64>,
+    /// Chat message warning players, where `{remaining}` is replaced with the time until `action`
+    pub warning_message: String,
+    /// An optional countdown shown above the hotbar from the first warning on,
+    /// where `{remaining}` is replaced with the time until `action`
+    pub countdown_message: Option<String>,
+    /// What happens to players once the time is up
+    pub action: super::ExitAction,
+}
@@ -1,7 +1,7 @@
 use azalea_core::position::{BlockPos, Vec3};
 
 /// A location in the world.
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Location {
     x: f64,
     y: f64,
@@ -47,6 +47,16 @@ impl Location {
         self.pitch
     }
 
+    /// Returns this location moved to the given coordinates.
+    pub fn with_position(self, x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z, ..self }
+    }
+
+    /// Returns this location turned to the given rotation.
+    pub fn with_rotation(self, yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch, ..self }
+    }
+
     /// Converts this location to a Vec3.
     pub fn to_vec3(&self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
@@ -71,3 +81,28 @@ impl std::fmt::Display for Location {
         )
     }
 }
+
+/// A block position in the world.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct BlockLocation {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+impl BlockLocation {
+    /// Converts this location to a BlockPos.
+    pub fn to_block_pos(&self) -> BlockPos {
+        BlockPos {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl std::fmt::Display for BlockLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x={}, y={}, z={})", self.x, self.y, self.z)
+    }
+}
@@ -0,0 +1,43 @@
+use super::{BlockLocation, Location};
+
+/// Sends players back to the spawn location when they fall or wander off.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FallProtection {
+    /// Players below this Y coordinate are sent back
+    pub min_y: f64,
+    /// An optional region (inclusive block bounds) that players are sent back from when leaving it
+    pub region: Option<Region>,
+    /// An optional chat message sent to players that were sent back
+    pub message: Option<String>,
+    /// An optional sound played to players that were sent back (e.g. `minecraft:entity.enderman.teleport`)
+    pub sound: Option<String>,
+}
+
+/// A box-shaped region of the world.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Region {
+    /// The corner with the lowest coordinates
+    pub min: BlockLocation,
+    /// The corner with the highest coordinates
+    pub max: BlockLocation,
+}
+
+impl FallProtection {
+    /// Returns whether a player at the given location should be sent back.
+    pub fn should_return(&self, location: &Location) -> bool {
+        if location.y() < self.min_y {
+            return true;
+        }
+        let Some(region) = &self.region else {
+            return false;
+        };
+        let (pos, min, max) = (
+            location.to_block_pos(),
+            region.min.to_block_pos(),
+            region.max.to_block_pos(),
+        );
+        !(min.x..=max.x).contains(&pos.x)
+            || !(min.y..=max.y).contains(&pos.y)
+            || !(min.z..=max.z).contains(&pos.z)
+    }
+}
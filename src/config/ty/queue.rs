@@ -0,0 +1,22 @@
+/// Makes the limbo a waiting room, releasing players in order of priority and arrival.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QueueConfig {
+    /// Message shown above the hotbar of waiting players, where `{position}`, `{size}`
+    /// and `{eta}` (the estimated time until the player is released) are replaced
+    pub message: String,
+    /// Text replacing `{eta}` while the wait cannot be estimated yet
+    pub unknown_eta: String,
+    /// What happens to players once they are released
+    pub action: super::ExitAction,
+    /// Number of players released every `release_interval` (0 to only release players
+    /// with the `release <amount>` console command)
+    pub release_amount: usize,
+    /// Time (in seconds) between two automatic releases
+    pub release_interval: u64,
+    /// Path to an optional file assigning priorities to players (higher ones are released first),
+    /// with one `<name or UUID> <priority>` per line
+    pub priority_file: Option<String>,
+    /// Name of an optional game profile property (forwarded by the proxy) holding the priority
+    /// of the player, used for players not in the priority file
+    pub priority_property: Option<String>,
+}
@@ -0,0 +1,6 @@
+/// Shows the players in the limbo to each other.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OtherPlayersConfig {
+    /// Horizontal distance (in blocks) within which other players are shown
+    pub tracking_range: f64,
+}
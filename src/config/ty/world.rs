@@ -0,0 +1,200 @@
+/// Where the limbo world is loaded from.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorldSource {
+    /// A world created by the configured generator.
+    #[default]
+    Generated,
+    /// A vanilla world folder, read from its Anvil region files.
+    Anvil {
+        /// Path to the world folder (the one containing the `region` folder)
+        path: String,
+    },
+    /// A Sponge schematic (`.schem`, v2 or v3) or vanilla structure (`.nbt`) file,
+    /// pasted into a world created by the configured generator.
+    Schematic {
+        /// Path to the schematic or structure file
+        path: String,
+        /// Where the minimum corner of the schematic is placed
+        origin: super::BlockLocation,
+    },
+}
+
+/// The kind of terrain a generated world consists of.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeneratorKind {
+    /// Nothing but air.
+    #[default]
+    Void,
+    /// Layers of blocks, starting at the bottom of the world.
+    Flat {
+        /// The layers, from bottom to top
+        layers: Vec<FlatLayer>,
+    },
+}
+
+/// A layer of blocks in a flat world.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FlatLayer {
+    /// The block state the layer consists of (e.g. `minecraft:grass_block[snowy=false]`)
+    pub block: String,
+    /// The height of the layer in blocks
+    pub height: u32,
+}
+
+/// A square platform placed right below the spawn location.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpawnPlatform {
+    /// The block state the platform consists of
+    pub block: String,
+    /// The length of the platform's sides in blocks
+    pub size: u32,
+}
+
+/// World generator configuration.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GeneratorConfig {
+    /// The kind of terrain to generate
+    pub kind: GeneratorKind,
+    /// The biome of generated chunks (e.g. `minecraft:plains`)
+    pub biome: String,
+    /// An optional platform to place below the spawn location
+    pub platform: Option<SpawnPlatform>,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            kind: GeneratorKind::default(),
+            biome: "minecraft:plains".into(),
+            platform: None,
+        }
+    }
+}
+
+/// How the light levels of the world are determined.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LightingMode {
+    /// Sky light and light from light-emitting blocks is computed.
+    #[default]
+    Computed,
+    /// Every block is fully lit.
+    Fullbright,
+}
+
+/// The time of day in the world.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TimeConfig {
+    /// Time of day in ticks (0 is sunrise, 6000 is noon, 12000 is sunset and 18000 is midnight)
+    pub time_of_day: u64,
+    /// Whether the time of day advances on the client (the `doDaylightCycle` game rule)
+    pub daylight_cycle: bool,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            time_of_day: 6000,
+            daylight_cycle: false,
+        }
+    }
+}
+
+/// The weather in the world.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Weather {
+    /// No rain.
+    #[default]
+    Clear,
+    /// Rain (or snow, depending on the biome).
+    Rain,
+    /// Rain along with a darkened sky.
+    Thunder,
+}
+
+/// Game rules that are visible to the client.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GameRules {
+    /// Whether players respawn without seeing the death screen (the `doImmediateRespawn` game rule)
+    pub immediate_respawn: bool,
+    /// Whether players can only craft unlocked recipes (the `doLimitedCrafting` game rule)
+    pub limited_crafting: bool,
+}
+
+/// World configuration.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorldConfig {
+    /// Where the world is loaded from
+    pub source: WorldSource,
+    /// The dimension type of the world, which determines its height, sky and fog
+    #[serde(default = "default_dimension_type")]
+    pub dimension_type: String,
+    /// Radius (in chunks) around the spawn location that is loaded (chunks beyond it are sent empty,
+    /// and any part of a schematic beyond it is left out)
+    pub radius: u32,
+    /// Path to the vanilla `blocks.json` report, used to resolve block properties
+    pub block_report: Option<String>,
+    /// How the light levels of the world are determined
+    #[serde(default)]
+    pub lighting: LightingMode,
+    /// The weather in the world
+    #[serde(default)]
+    pub weather: Weather,
+    /// The time of day in the world
+    #[serde(default)]
+    pub time: TimeConfig,
+    /// Game rules that are visible to the client
+    #[serde(default)]
+    pub game_rules: GameRules,
+    /// How new chunks are generated
+    #[serde(default)]
+    pub generator: GeneratorConfig,
+    /// An optional world border, players crossing it are sent back to the spawn location
+    pub border: Option<super::WorldBorder>,
+}
+
+/// The time of day, weather and game rules of the world, which are reloaded with the `reload` command.
+#[derive(Debug, Clone)]
+pub struct Ambience {
+    /// The weather in the world
+    pub weather: Weather,
+    /// The time of day in the world
+    pub time: TimeConfig,
+    /// Game rules that are visible to the client
+    pub game_rules: GameRules,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            source: WorldSource::default(),
+            dimension_type: default_dimension_type(),
+            radius: 6,
+            block_report: None,
+            lighting: LightingMode::default(),
+            weather: Weather::default(),
+            time: TimeConfig::default(),
+            game_rules: GameRules::default(),
+            generator: GeneratorConfig::default(),
+            border: None,
+        }
+    }
+}
+
+fn default_dimension_type() -> String {
+    "minecraft:overworld".into()
+}
+
+impl WorldConfig {
+    /// Returns the time of day, weather and game rules of the world.
+    pub fn ambience(&self) -> Ambience {
+        Ambience {
+            weather: self.weather,
+            time: self.time.clone(),
+            game_rules: self.game_rules.clone(),
+        }
+    }
+}
@@ -7,6 +7,7 @@ mod config;
 mod network;
 mod player;
 mod utils;
+mod world;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None, propagate_version = true)]
@@ -0,0 +1,229 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use azalea_protocol::{
+    connect::Connection,
+    packets::{
+        handshaking::client_intention_packet::ClientIntentionPacket,
+        status::{
+            serverbound_status_request_packet::ServerboundStatusRequestPacket,
+            ClientboundStatusPacket,
+        },
+        ConnectionProtocol,
+    },
+};
+use tracing::*;
+
+use super::StatusClientConnection;
+use crate::{
+    config::ty::{AutoReturnConfig, Backend},
+    player::Player,
+};
+
+/// The backend servers of the proxy that are watched, and since when they are up.
+#[derive(Default)]
+pub struct Backends {
+    /// When each backend (by name) was last seen coming up, if it is up
+    up_since: Mutex<HashMap<String, Instant>>,
+}
+
+impl Backends {
+    /// Returns the backend a player came from, from its forwarded property or else the first one.
+    pub fn backend_of<'a>(config: &'a AutoReturnConfig, player: &Player) -> Option<&'a Backend> {
+        let from_property = || {
+            let name = player.property(config.server_property.as_ref()?)?;
+            config
+                .backends
+                .iter()
+                .find(|backend| backend.name.eq_ignore_ascii_case(name))
+        };
+        from_property().or_else(|| config.backends.first())
+    }
+
+    /// Returns since when a backend is up, if it is.
+    pub fn up_since(&self, name: &str) -> Option<Instant> {
+        self.up_since.lock().unwrap().get(name).copied()
+    }
+
+    /// Polls a backend until the server stops, keeping track of whether it is up.
+    pub async fn poll(&self, config: &AutoReturnConfig, backend: &Backend) {
+        let period = Duration::from_secs(config.poll_interval.max(1));
+        let timeout = Duration::from_secs(config.timeout);
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let result = tokio::time::timeout(timeout, request_status(&backend.address)).await;
+            let result = result.unwrap_or_else(|_| Err(anyhow::anyhow!("Timed out")));
+            let mut up_since = self.up_since.lock().unwrap();
+            match (result, up_since.contains_key(&backend.name)) {
+                (Ok(()), false) => {
+                    info!(backend = backend.name, "Backend server is up");
+                    up_since.insert(backend.name.clone(), Instant::now());
+                }
+                (Err(err), true) => {
+                    info!(backend = backend.name, "Backend server is down: {err:#}");
+                    up_since.remove(&backend.name);
+                }
+                (Err(err), false) => {
+                    trace!(
+                        backend = backend.name,
+                        "Backend server is still down: {err:#}"
+                    );
+                }
+                (Ok(()), true) => {}
+            }
+        }
+    }
+}
+
+/// Requests the status of a server, like the server list of a client does.
+#[tracing::instrument(level = "trace", err)]
+async fn request_status(address: &str) -> anyhow::Result<()> {
+    trace!("Requesting server status");
+    let (host, port) = split_address(address)?;
+    let addr = tokio::net::lookup_host((host, port))
+        .await?
+        .next()
+        .context("Address did not resolve")?;
+
+    let mut conn = Connection::new(&addr).await?;
+    conn.write(
+        ClientIntentionPacket {
+            protocol_version: super::PROTOCOL_VERSION,
+            hostname: host.into(),
+            port,
+            intention: ConnectionProtocol::Status,
+        }
+        .get(),
+    )
+    .await?;
+    let mut conn: StatusClientConnection = conn.status();
+    conn.write(ServerboundStatusRequestPacket {}.get()).await?;
+    match conn.read().await? {
+        ClientboundStatusPacket::StatusResponse(_) => Ok(()),
+        packet => anyhow::bail!("Unexpected status packet: {packet:?}"),
+    }
+}
+
+/// Splits a `host:port` address into its host (without the brackets around an IPv6 address) and port.
+fn split_address(address: &str) -> anyhow::Result<(&str, u16)> {
+    let (host, port) = address
+        .rsplit_once(':')
+        .context("Address is missing a port")?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    let port = port.parse().context("Address has an invalid port")?;
+    Ok((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use azalea_protocol::packets::status::{
+        clientbound_status_response_packet::{ClientboundStatusResponsePacket, Players, Version},
+        ServerboundStatusPacket,
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::network::HandshakeConnection;
+
+    /// Answers the status requests of every connection, like a backend server would.
+    async fn serve_status(listener: TcpListener) {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut conn = HandshakeConnection::wrap(stream);
+            if conn.read().await.is_err() {
+                continue;
+            }
+            let mut conn = conn.status();
+            if let Ok(ServerboundStatusPacket::StatusRequest(_)) = conn.read().await {
+                let response = ClientboundStatusResponsePacket {
+                    description: "Backend".into(),
+                    favicon: None,
+                    players: Players {
+                        max: 20,
+                        online: 0,
+                        sample: vec![],
+                    },
+                    version: Version {
+                        name: crate::network::VERSION.into(),
+                        protocol: crate::network::PROTOCOL_VERSION,
+                    },
+                    enforces_secure_chat: Some(false),
+                };
+                let _ = conn.write(response.get()).await;
+            }
+        }
+    }
+
+    /// Waits until the condition holds, failing the test if it takes too long.
+    async fn wait_until(mut condition: impl FnMut() -> bool) {
+        let wait = async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), wait)
+            .await
+            .expect("Timed out waiting for the condition");
+    }
+
+    #[test]
+    fn splits_addresses() {
+        assert_eq!(
+            split_address("127.0.0.1:25566").unwrap(),
+            ("127.0.0.1", 25566)
+        );
+        assert_eq!(split_address("[::1]:25566").unwrap(), ("::1", 25566));
+        assert_eq!(
+            split_address("lobby.example:25565").unwrap(),
+            ("lobby.example", 25565)
+        );
+        assert!(split_address("127.0.0.1").is_err());
+    }
+
+    #[tokio::test]
+    async fn requests_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(serve_status(listener));
+        request_status(&address).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tracks_backend_going_up_and_down() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let config = AutoReturnConfig {
+            backends: vec![Backend {
+                name: "survival".into(),
+                address,
+            }],
+            poll_interval: 1,
+            timeout: 1,
+            server_property: None,
+            message: String::new(),
+        };
+        let backends = Arc::new(Backends::default());
+        let server = tokio::spawn(serve_status(listener));
+        let poller = {
+            let backends = Arc::clone(&backends);
+            tokio::spawn(async move { backends.poll(&config, &config.backends[0]).await })
+        };
+
+        wait_until(|| backends.up_since("survival").is_some()).await;
+        // Stopping the server closes its listener, so the next status request is refused
+        server.abort();
+        wait_until(|| backends.up_since("survival").is_none()).await;
+        poller.abort();
+    }
+}
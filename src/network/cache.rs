@@ -0,0 +1,196 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use azalea_buf::{McBufVarReadable, McBufWritable};
+use azalea_core::position::ChunkPos;
+use azalea_protocol::{
+    packets::{
+        configuration::clientbound_registry_data_packet::ClientboundRegistryDataPacket,
+        game::clientbound_level_chunk_with_light_packet::{
+            ClientboundLevelChunkPacketData, ClientboundLevelChunkWithLightPacket,
+        },
+    },
+    write::serialize_packet,
+};
+use simdnbt::{
+    owned::{Nbt, NbtCompound, NbtTag},
+    Serialize,
+};
+use tracing::*;
+
+use crate::{
+    config::{self, ty::LightingMode},
+    utils::registry_data,
+    world::{self, light::ChunkLight, World, WorldChunk},
+};
+
+static mut CACHE: Option<PacketCache> = None;
+
+/// Packets that are the same for every player, encoded once and framed by each connection they are written to.
+/// Must be rebuilt with [`init`] whenever the world or registry data changes.
+struct PacketCache {
+    /// The registry data packet of the configuration phase
+    registry_data: Box<[u8]>,
+    /// The chunk data packet of every loaded chunk
+    chunks: HashMap<ChunkPos, Box<[u8]>>,
+    /// The chunk data packet sent in place of chunks that are not loaded, encoded at (0, 0)
+    empty_chunk: Box<[u8]>,
+    /// Where the chunk position starts in the empty chunk packet (right after the packet ID)
+    empty_chunk_pos_offset: usize,
+}
+
+pub fn init() -> anyhow::Result<()> {
+    info!("Initializing packet cache");
+
+    let registry_holder = registry_data::get().clone();
+    let registry_data = encode(ClientboundRegistryDataPacket { registry_holder }.get())?;
+    let cache = PacketCache::new(registry_data, world::get(), config::get().world.lighting)?;
+    unsafe { CACHE = Some(cache) };
+    Ok(())
+}
+
+/// Initializes the cache with a world of a single loaded chunk at (0, 0) and no registry data.
+#[cfg(test)]
+pub fn init_test_world() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let dimension = world::Dimension {
+            min_y: 0,
+            height: 16,
+            has_skylight: true,
+        };
+        let mut world = world::World::new(dimension, 0);
+        world.insert_chunk(ChunkPos::new(0, 0), world.empty_chunk());
+        let cache = PacketCache::new(Box::default(), &world, LightingMode::Computed).unwrap();
+        unsafe { CACHE = Some(cache) };
+    });
+}
+
+impl PacketCache {
+    fn new(
+        registry_data: Box<[u8]>,
+        world: &World,
+        lighting: LightingMode,
+    ) -> anyhow::Result<Self> {
+        let chunks = world
+            .chunks()
+            .map(|(pos, chunk)| Ok((*pos, encode(chunk_packet(*pos, chunk)?.get())?)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        let mut empty_chunk = world.empty_chunk();
+        empty_chunk.light = ChunkLight::empty(world.dimension(), lighting);
+        let empty_chunk = encode(chunk_packet(ChunkPos::new(0, 0), &empty_chunk)?.get())?;
+        let empty_chunk_pos_offset = {
+            let mut cursor = std::io::Cursor::new(&*empty_chunk);
+            u32::var_read_from(&mut cursor)?;
+            cursor.position() as usize
+        };
+        debug!(
+            registry_data = registry_data.len(),
+            chunks = chunks.values().map(|c| c.len()).sum::<usize>(),
+            "Packets encoded"
+        );
+
+        Ok(Self {
+            registry_data,
+            chunks,
+            empty_chunk,
+            empty_chunk_pos_offset,
+        })
+    }
+}
+
+fn get() -> &'static PacketCache {
+    unsafe { CACHE.as_ref().expect("Packet cache not initialized") }
+}
+
+/// Returns the encoded registry data packet.
+pub fn registry_data() -> &'static [u8] {
+    &get().registry_data
+}
+
+/// Returns the encoded chunk data packet of a chunk.
+/// Chunks that are not loaded are sent as the encoded empty chunk, with only their position replaced.
+pub fn chunk(pos: ChunkPos) -> Cow<'static, [u8]> {
+    let cache = get();
+    match cache.chunks.get(&pos) {
+        Some(data) => Cow::Borrowed(data),
+        None => {
+            // The position is written as two big-endian ints, followed by the rest of the chunk
+            let mut data = cache.empty_chunk.to_vec();
+            let offset = cache.empty_chunk_pos_offset;
+            data[offset..offset + 4].copy_from_slice(&pos.x.to_be_bytes());
+            data[offset + 4..offset + 8].copy_from_slice(&pos.z.to_be_bytes());
+            Cow::Owned(data)
+        }
+    }
+}
+
+/// Serializes a packet into its ID and payload, without the length prefix
+/// (which is added by [`WriteConnectionExt::write_encoded`](super::ext::WriteConnectionExt::write_encoded)).
+fn encode<P>(packet: P) -> std::io::Result<Box<[u8]>>
+where
+    P: azalea_protocol::packets::ProtocolPacket + std::fmt::Debug,
+{
+    let data = serialize_packet(&packet).map_err(std::io::Error::other)?;
+    Ok(data.into_boxed_slice())
+}
+
+fn chunk_packet(
+    pos: ChunkPos,
+    chunk: &WorldChunk,
+) -> std::io::Result<ClientboundLevelChunkWithLightPacket> {
+    let mut data = Vec::new();
+    chunk.chunk.write_into(&mut data)?;
+
+    let mut heightmaps = NbtCompound::new();
+    for (kind, heightmap) in &chunk.chunk.heightmaps {
+        let data = heightmap.data.data.iter().map(|x| *x as i64).collect();
+        heightmaps.insert(format!("{kind}"), NbtTag::LongArray(data));
+    }
+
+    Ok(ClientboundLevelChunkWithLightPacket {
+        x: pos.x,
+        z: pos.z,
+        chunk_data: ClientboundLevelChunkPacketData {
+            heightmaps: Nbt::Some(heightmaps.to_nbt()),
+            data,
+            block_entities: chunk.block_entities.clone(),
+        },
+        light_data: chunk.light.to_packet_data(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_protocol::{
+        connect::Connection,
+        packets::configuration::{
+            clientbound_keep_alive_packet::ClientboundKeepAlivePacket,
+            ClientboundConfigurationPacket, ServerboundConfigurationPacket,
+        },
+    };
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+    use crate::network::{ext::ConnectionExt, ConfigurationConnection};
+
+    #[tokio::test]
+    async fn encoded_packet_is_framed_when_written() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let mut server = ConfigurationConnection::wrap(listener.accept().await.unwrap().0);
+        let mut client =
+            Connection::<ClientboundConfigurationPacket, ServerboundConfigurationPacket>::wrap(
+                stream,
+            );
+
+        let data = encode(ClientboundKeepAlivePacket { id: 42 }.get()).unwrap();
+        server.write_encoded(&data).await.unwrap();
+        // The client reads the frame with `azalea_protocol::read`, like a vanilla client would
+        match client.read().await.unwrap() {
+            ClientboundConfigurationPacket::KeepAlive(packet) => assert_eq!(packet.id, 42),
+            packet => panic!("Unexpected packet: {packet:?}"),
+        }
+    }
+}
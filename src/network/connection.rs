@@ -42,8 +42,16 @@ async fn try_handle(
         ClientIntention::Status => return phase::status::try_handle(conn.status(), &server).await,
         ClientIntention::Login => phase::login::try_handle(conn.login(), addr, &server).await?,
     };
-    let player_record = format!("{}", player.lock().await);
+    let (player_record, uuid) = {
+        let player = player.lock().await;
+        (format!("{player}"), player.uuid())
+    };
     tracing::Span::current().record("player", tracing::field::display(player_record));
-    let conn = phase::configuration::try_handle(conn, player).await?;
-    phase::game::try_handle(conn, &server, player).await // no further phases, we've reached the gameloop
+    let result = async {
+        let conn = phase::configuration::try_handle(conn, player).await?;
+        phase::game::try_handle(conn, &server, player).await // no further phases, we've reached the gameloop
+    }
+    .await;
+    server.remove_player(uuid, player);
+    result
 }
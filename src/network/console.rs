@@ -0,0 +1,60 @@
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::*;
+
+use crate::{
+    config,
+    network::{
+        phase,
+        server::{AServer, Server},
+    },
+};
+
+impl Server {
+    /// Handles the commands typed in the console until it is closed.
+    pub async fn handle_console(self: AServer) {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut args = line.split_whitespace();
+            match args.next() {
+                None => continue,
+                Some("release") => self.release_command(args.next()),
+                Some("queue") => match self.queue() {
+                    Some(queue) => info!("{} players are waiting in the queue", queue.size()),
+                    None => warn!("The queue is disabled"),
+                },
+                Some("reload") => self.reload_command().await,
+                Some(command) => warn!("Unknown console command: {command}"),
+            }
+        }
+        debug!("Console closed");
+    }
+
+    /// Releases players from the front of the queue (`release [amount]`).
+    fn release_command(self: &AServer, amount: Option<&str>) {
+        let Some(queue) = self.queue() else {
+            warn!("The queue is disabled");
+            return;
+        };
+        let Ok(amount) = amount.unwrap_or("1").parse() else {
+            warn!("Usage: release [amount]");
+            return;
+        };
+        let released = queue.release(amount);
+        info!("Released {released} players from the queue");
+    }
+
+    /// Reloads the time of day, weather and game rules from the configuration file (`reload`),
+    /// sending them to every player.
+    async fn reload_command(self: &AServer) {
+        let config = match config::Config::read(config::path()) {
+            Ok(config) => config,
+            Err(err) => {
+                warn!("Failed to reload the configuration: {err:#}");
+                return;
+            }
+        };
+        self.set_ambience(config.world.ambience());
+        phase::game::broadcast_ambience(self).await;
+        info!("Reloaded the time of day, weather and game rules");
+    }
+}
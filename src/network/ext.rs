@@ -1,7 +1,7 @@
 use std::{fmt::Debug, time::Duration};
 
 use azalea_protocol::{
-    connect::Connection,
+    connect::{Connection, WriteConnection},
     packets::{
         configuration::clientbound_disconnect_packet::ClientboundDisconnectPacket as ClientboundConfigurationDisconnectPacket,
         game::clientbound_disconnect_packet::ClientboundDisconnectPacket as ClientboundGameDisconnectPacket,
@@ -12,7 +12,7 @@ use azalea_protocol::{
 use tokio::io::AsyncWriteExt;
 use tracing::*;
 
-use super::{ConfigurationConnection, ConnectionPhase, GameConnection, LoginConnection, Result};
+use super::{ConfigurationConnection, ConnectionPhase, GameWriter, LoginConnection, Result};
 
 pub static mut READ_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -31,6 +31,9 @@ pub trait ConnectionExt<R, W> {
     async fn read_timeout(&mut self, phase: ConnectionPhase) -> Result<R>;
     /// Write a packet to the other side of the connection.
     async fn write_raw(&mut self, data: &[u8]) -> std::io::Result<()>;
+    /// Write an encoded packet (its ID and payload, see [`super::cache`]) to the other side
+    /// of the connection, framing it like any other packet.
+    async fn write_encoded(&mut self, data: &[u8]) -> std::io::Result<()>;
 }
 
 impl<R, W> ConnectionExt<R, W> for Connection<R, W>
@@ -43,12 +46,33 @@ where
     }
 
     async fn write_raw(&mut self, data: &[u8]) -> std::io::Result<()> {
-        match self.writer.raw.write_stream.write_all(data).await {
+        self.writer.write_raw(data).await
+    }
+
+    async fn write_encoded(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.writer.write_encoded(data).await
+    }
+}
+
+pub trait WriteConnectionExt {
+    /// Write raw bytes to the other side of the connection.
+    async fn write_raw(&mut self, data: &[u8]) -> std::io::Result<()>;
+    /// Write an encoded packet (its ID and payload, see [`super::cache`]) to the other side
+    /// of the connection, framing it like any other packet.
+    async fn write_encoded(&mut self, data: &[u8]) -> std::io::Result<()>;
+}
+
+impl<W> WriteConnectionExt for WriteConnection<W>
+where
+    W: ProtocolPacket + Debug,
+{
+    async fn write_raw(&mut self, data: &[u8]) -> std::io::Result<()> {
+        match self.raw.write_stream.write_all(data).await {
             Ok(()) => Ok(()),
             Err(err) => {
                 if err.kind() == std::io::ErrorKind::BrokenPipe {
                     debug!("Broken pipe, shutting down connection.");
-                    if let Err(e) = self.writer.shutdown().await {
+                    if let Err(e) = self.shutdown().await {
                         error!("Couldn't shut down: {e}");
                     }
                 }
@@ -56,6 +80,11 @@ where
             }
         }
     }
+
+    async fn write_encoded(&mut self, data: &[u8]) -> std::io::Result<()> {
+        // Prepends the length of the packet (compressing and encrypting it if enabled)
+        self.raw.write(data).await
+    }
 }
 
 pub trait ConnectionPhaseExt {
@@ -86,7 +115,7 @@ impl ConnectionPhaseExt for ConfigurationConnection {
     }
 }
 
-impl ConnectionPhaseExt for GameConnection {
+impl ConnectionPhaseExt for GameWriter {
     async fn write_disconnect(&mut self, reason: String) -> std::io::Result<()> {
         self.write(
             ClientboundGameDisconnectPacket {
@@ -1,6 +1,10 @@
+pub mod backends;
+pub mod cache;
 pub mod connection;
+pub mod console;
 pub mod ext;
 pub mod phase;
+pub mod queue;
 pub mod server;
 pub mod utils;
 
@@ -17,6 +21,12 @@ pub type StatusConnection = azalea_protocol::connect::Connection<
     azalea_protocol::packets::status::ClientboundStatusPacket,
 >;
 
+/// A status connection to another server, see [`backends`].
+pub type StatusClientConnection = azalea_protocol::connect::Connection<
+    azalea_protocol::packets::status::ClientboundStatusPacket,
+    azalea_protocol::packets::status::ServerboundStatusPacket,
+>;
+
 pub type LoginConnection = azalea_protocol::connect::Connection<
     azalea_protocol::packets::login::ServerboundLoginPacket,
     azalea_protocol::packets::login::ClientboundLoginPacket,
@@ -32,6 +42,15 @@ pub type GameConnection = azalea_protocol::connect::Connection<
     azalea_protocol::packets::game::ClientboundGamePacket,
 >;
 
+/// The reading half of a game connection, see [`GameConnection::into_split`].
+pub type GameReader =
+    azalea_protocol::connect::ReadConnection<azalea_protocol::packets::game::ServerboundGamePacket>;
+
+/// The writing half of a game connection, see [`GameConnection::into_split`].
+pub type GameWriter = azalea_protocol::connect::WriteConnection<
+    azalea_protocol::packets::game::ClientboundGamePacket,
+>;
+
 pub type Result<T> = std::result::Result<T, ConnectionError>;
 
 #[derive(thiserror::Error, Debug)]
@@ -29,7 +29,7 @@ pub async fn try_handle(
         {
             Ok(ServerboundConfigurationPacket::ClientInformation(packet)) => {
                 debug!("Received client information from client");
-                utils::set_skin_layers(player, packet.information.model_customization).await;
+                utils::set_client_information(player, packet.information).await;
             }
             Ok(ServerboundConfigurationPacket::CustomPayload(packet)) => {
                 debug!("Received custom payload from client");
@@ -7,16 +7,16 @@ use azalea_protocol::{
     packets::configuration::{
         clientbound_custom_payload_packet::ClientboundCustomPayloadPacket,
         clientbound_finish_configuration_packet::ClientboundFinishConfigurationPacket,
-        clientbound_registry_data_packet::ClientboundRegistryDataPacket,
+        serverbound_client_information_packet::ClientInformation,
     },
 };
 use tracing::*;
 
 use crate::{
     config, internal_error,
-    network::{self, server::PlayerRef, ConfigurationConnection, GameConnection},
-    player::skin,
-    utils::registry_data,
+    network::{
+        self, cache, ext::ConnectionExt, server::PlayerRef, ConfigurationConnection, GameConnection,
+    },
 };
 
 #[tracing::instrument(level = "trace", skip_all, err)]
@@ -24,9 +24,7 @@ pub async fn send_configurations(conn: &mut ConfigurationConnection) -> network:
     trace!("Sending server brand to client");
     send_server_brand(conn).await?;
     trace!("Sending registry data to client");
-    let registry_holder = registry_data::get();
-    conn.write(ClientboundRegistryDataPacket { registry_holder }.get())
-        .await?;
+    conn.write_encoded(cache::registry_data()).await?;
     trace!("Signaling client to finish configuration");
     conn.write(ClientboundFinishConfigurationPacket {}.get())
         .await?;
@@ -59,9 +57,11 @@ pub async fn read_string(
 }
 
 #[tracing::instrument(level = "trace", skip_all)]
-pub async fn set_skin_layers(player: &PlayerRef, layers: skin::SkinLayers) {
-    trace!("Setting skin layers for player");
-    player.lock().await.set_skin_layers(layers);
+pub async fn set_client_information(player: &PlayerRef, information: ClientInformation) {
+    trace!("Setting client information for player");
+    let mut player = player.lock().await;
+    player.set_skin_layers(information.model_customization);
+    player.set_client_view_distance(information.view_distance as u32);
 }
 
 /// Change our state from configuration to game.
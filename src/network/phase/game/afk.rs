@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+use tracing::*;
+
+use super::{exit, template, utils};
+use crate::{
+    config::{self, ty::AfkConfig},
+    network::{self, server::PlayerRef, GameWriter},
+    player::Player,
+};
+
+/// Warns the player while it is idle, and removes it from the limbo once it has been idle too long.
+#[derive(Default)]
+pub struct AfkTracker {
+    /// When the player was last warned, if it has been warned since it was last active
+    warned_at: Option<Instant>,
+    /// Whether the player has been removed since it was last active
+    removed: bool,
+}
+
+impl AfkTracker {
+    pub async fn tick(&mut self, conn: &mut GameWriter, player: &PlayerRef) -> network::Result<()> {
+        let Some(config) = &config::get().afk else {
+            return Ok(());
+        };
+        let idle_time = {
+            let player = player.lock().await;
+            if is_exempt(config, &player) {
+                return Ok(());
+            }
+            player.idle_time()
+        };
+
+        if idle_time < Duration::from_secs(config.warn_after) {
+            self.warned_at = None;
+            self.removed = false;
+            return Ok(());
+        }
+        let kick_after = Duration::from_secs(config.kick_after);
+        if idle_time >= kick_after {
+            // Sending the player to another server may fail, in which case it stays here
+            if !self.removed {
+                info!(?idle_time, "Removing idle player");
+                self.removed = true;
+                exit::run(conn, &config.action).await?;
+            }
+            return Ok(());
+        }
+        let interval = Duration::from_secs(config.warning_interval);
+        if self
+            .warned_at
+            .is_some_and(|warned_at| warned_at.elapsed() < interval)
+        {
+            return Ok(());
+        }
+        debug!(?idle_time, "Warning idle player");
+        self.warned_at = Some(Instant::now());
+        let remaining = template::format_duration(kick_after - idle_time);
+        let message = config.warning_message.replace("{remaining}", &remaining);
+        utils::signal_system_message(conn, &message).await
+    }
+}
+
+/// Returns whether the player is never considered idle.
+fn is_exempt(config: &AfkConfig, player: &Player) -> bool {
+    let uuid = player.uuid().to_string();
+    config
+        .bypass
+        .iter()
+        .any(|entry| entry.eq_ignore_ascii_case(player.name()) || entry.eq_ignore_ascii_case(&uuid))
+}
@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config,
+    network::{
+        self,
+        backends::Backends,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+};
+
+/// Sends the player back to the backend it came from, once that backend is up again.
+#[derive(Default)]
+pub struct AutoReturn {
+    /// Whether the player has been sent back
+    returned: bool,
+}
+
+impl AutoReturn {
+    pub async fn tick(
+        &mut self,
+        conn: &mut GameWriter,
+        server: &AServer,
+        player: &PlayerRef,
+        joined_at: Instant,
+    ) -> network::Result<()> {
+        let (Some(backends), Some(config)) = (server.backends(), &config::get().auto_return) else {
+            return Ok(());
+        };
+        if self.returned {
+            return Ok(());
+        }
+        let Some(backend) = Backends::backend_of(config, &*player.lock().await) else {
+            return Ok(());
+        };
+        // Only a backend that came up after the player joined is the one it is waiting for
+        if !backends
+            .up_since(&backend.name)
+            .is_some_and(|up_since| up_since > joined_at)
+        {
+            return Ok(());
+        }
+        info!(
+            backend = backend.name,
+            "Sending player back to backend server"
+        );
+        self.returned = true;
+        let message = config.message.replace("{server}", &backend.name);
+        utils::signal_system_message(conn, &message).await?;
+        utils::signal_proxy_connect(conn, &backend.name).await
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use azalea_protocol::packets::game::clientbound_system_chat_packet::ClientboundSystemChatPacket;
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config::{self, ty::ChatMode},
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+};
+
+/// Handles the chat messages of a player according to the chat configuration.
+#[derive(Default)]
+pub struct Chat {
+    /// When the player last sent a message that was accepted
+    last_message: Option<Instant>,
+}
+
+impl Chat {
+    #[tracing::instrument(level = "trace", skip_all, err)]
+    pub async fn handle(
+        &mut self,
+        conn: &mut GameWriter,
+        server: &AServer,
+        player: &PlayerRef,
+        message: String,
+    ) -> network::Result<()> {
+        let config = &config::get().chat;
+        let name = player.lock().await.name().to_string();
+        info!(player = name, message, "Chat message received");
+
+        if config.mode == ChatMode::Disabled {
+            return utils::signal_system_message(conn, &config.disabled_message).await;
+        }
+        if message.chars().count() > config.max_length {
+            return utils::signal_system_message(conn, &config.too_long_message).await;
+        }
+        let cooldown = Duration::from_millis(config.cooldown);
+        if self
+            .last_message
+            .is_some_and(|last| last.elapsed() < cooldown)
+        {
+            return utils::signal_system_message(conn, &config.cooldown_message).await;
+        }
+        self.last_message = Some(Instant::now());
+
+        let text = config
+            .format
+            .replace("{player}", &name)
+            .replace("{message}", &message);
+        match config.mode {
+            ChatMode::Local => {
+                let packet = ClientboundSystemChatPacket {
+                    content: text.into(),
+                    overlay: false,
+                };
+                server.broadcast(packet.get()).await;
+                Ok(())
+            }
+            _ => utils::signal_system_message(conn, &text).await,
+        }
+    }
+}
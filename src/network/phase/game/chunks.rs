@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+
+use azalea_core::position::ChunkPos;
+use tracing::*;
+
+use super::utils;
+use crate::network::{self, GameWriter};
+
+/// Chunks per batch until the client reports its own rate (the vanilla default).
+const DEFAULT_CHUNKS_PER_BATCH: f32 = 9.0;
+/// Upper bound for the rate requested by the client.
+const MAX_CHUNKS_PER_BATCH: f32 = 64.0;
+
+/// Keeps track of the chunks a client has loaded, streaming chunks around it as it moves.
+pub struct ChunkSender {
+    /// The chunk the client's view is centered on
+    center: Option<ChunkPos>,
+    /// Radius (in chunks) of the client's view
+    radius: i32,
+    /// Chunks that were sent to the client
+    sent: HashSet<ChunkPos>,
+    /// Chunks still to be sent, ordered from farthest to nearest
+    pending: Vec<ChunkPos>,
+    /// Amount of chunks in the next batch, as requested by the client
+    chunks_per_batch: f32,
+    /// Whether a batch has been sent that the client has not acknowledged yet
+    awaiting_ack: bool,
+}
+
+impl ChunkSender {
+    pub fn new(radius: i32) -> Self {
+        Self {
+            center: None,
+            radius,
+            sent: HashSet::new(),
+            pending: Vec::new(),
+            chunks_per_batch: DEFAULT_CHUNKS_PER_BATCH,
+            awaiting_ack: false,
+        }
+    }
+
+    /// Moves the client's view to a new center chunk.
+    #[tracing::instrument(level = "trace", skip_all, fields(x = center.x, z = center.z), err)]
+    pub async fn move_to(
+        &mut self,
+        conn: &mut GameWriter,
+        center: ChunkPos,
+    ) -> network::Result<()> {
+        if self.center == Some(center) {
+            return Ok(());
+        }
+        trace!("Moving chunk view");
+        self.center = Some(center);
+        utils::signal_center_chunk(conn, center).await?;
+        self.update(conn).await
+    }
+
+    /// Changes the radius of the client's view.
+    #[tracing::instrument(level = "trace", skip(self, conn), err)]
+    pub async fn set_radius(&mut self, conn: &mut GameWriter, radius: i32) -> network::Result<()> {
+        if self.radius == radius {
+            return Ok(());
+        }
+        trace!("Resizing chunk view");
+        self.radius = radius;
+        self.update(conn).await
+    }
+
+    /// Forgets the chunks that went out of view and queues the ones that came into view.
+    async fn update(&mut self, conn: &mut GameWriter) -> network::Result<()> {
+        let Some(center) = self.center else {
+            return Ok(());
+        };
+        let in_view = |pos: &ChunkPos| {
+            (pos.x - center.x).abs() <= self.radius && (pos.z - center.z).abs() <= self.radius
+        };
+        let forgotten: Vec<ChunkPos> = self.sent.iter().filter(|p| !in_view(p)).copied().collect();
+        for pos in forgotten {
+            self.sent.remove(&pos);
+            utils::signal_chunk_forget(conn, pos).await?;
+        }
+
+        self.pending.clear();
+        for x in center.x - self.radius..=center.x + self.radius {
+            for z in center.z - self.radius..=center.z + self.radius {
+                let pos = ChunkPos::new(x, z);
+                if !self.sent.contains(&pos) {
+                    self.pending.push(pos);
+                }
+            }
+        }
+        let distance = |pos: &ChunkPos| (pos.x - center.x).pow(2) + (pos.z - center.z).pow(2);
+        self.pending
+            .sort_unstable_by_key(|pos| std::cmp::Reverse(distance(pos)));
+
+        self.flush(conn).await
+    }
+
+    /// Handles the client acknowledging a chunk batch, sending the next one if needed.
+    pub async fn acknowledge(
+        &mut self,
+        conn: &mut GameWriter,
+        desired_chunks_per_tick: f32,
+    ) -> network::Result<()> {
+        trace!(desired_chunks_per_tick, "Client acknowledged chunk batch");
+        self.awaiting_ack = false;
+        // Clients report NaN when they have no measurements yet
+        if desired_chunks_per_tick.is_finite() {
+            self.chunks_per_batch = desired_chunks_per_tick.clamp(1.0, MAX_CHUNKS_PER_BATCH);
+        }
+        self.flush(conn).await
+    }
+
+    /// Sends the nearest pending chunks, unless the previous batch is still unacknowledged.
+    async fn flush(&mut self, conn: &mut GameWriter) -> network::Result<()> {
+        if self.awaiting_ack || self.pending.is_empty() {
+            return Ok(());
+        }
+        let len = (self.chunks_per_batch.ceil() as usize).min(self.pending.len());
+        let batch = self.pending.split_off(self.pending.len() - len);
+        self.sent.extend(&batch);
+        self.awaiting_ack = true;
+        utils::signal_chunk_batch_update(conn, batch.into_iter().rev().collect()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_protocol::packets::game::ClientboundGamePacket;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::network::cache;
+
+    async fn next(
+        packets: &mut mpsc::UnboundedReceiver<ClientboundGamePacket>,
+    ) -> ClientboundGamePacket {
+        packets.recv().await.unwrap()
+    }
+
+    /// Reads a chunk batch, returning the positions of its chunks in the order they were sent.
+    async fn batch(packets: &mut mpsc::UnboundedReceiver<ClientboundGamePacket>) -> Vec<ChunkPos> {
+        assert!(matches!(
+            next(packets).await,
+            ClientboundGamePacket::ChunkBatchStart(_)
+        ));
+        let mut chunks = Vec::new();
+        loop {
+            match next(packets).await {
+                ClientboundGamePacket::LevelChunkWithLight(packet) => {
+                    chunks.push(ChunkPos::new(packet.x, packet.z))
+                }
+                ClientboundGamePacket::ChunkBatchFinished(packet) => {
+                    assert_eq!(packet.batch_size as usize, chunks.len());
+                    return chunks;
+                }
+                packet => panic!("Unexpected packet: {packet:?}"),
+            }
+        }
+    }
+
+    async fn expect_center(
+        packets: &mut mpsc::UnboundedReceiver<ClientboundGamePacket>,
+        center: ChunkPos,
+    ) {
+        match next(packets).await {
+            ClientboundGamePacket::SetChunkCacheCenter(packet) => {
+                assert_eq!((packet.x, packet.z), (center.x, center.z))
+            }
+            packet => panic!("Unexpected packet: {packet:?}"),
+        }
+    }
+
+    fn distance(pos: &ChunkPos, center: ChunkPos) -> i32 {
+        (pos.x - center.x).pow(2) + (pos.z - center.z).pow(2)
+    }
+
+    #[tokio::test]
+    async fn streams_chunks_around_the_center() {
+        cache::init_test_world();
+        let (mut conn, mut packets) = utils::test_connection().await;
+        let mut sender = ChunkSender::new(1);
+        // No batch is pending, so only the rate changes
+        sender.acknowledge(&mut conn, 4.0).await.unwrap();
+
+        let center = ChunkPos::new(0, 0);
+        sender.move_to(&mut conn, center).await.unwrap();
+        expect_center(&mut packets, center).await;
+        let mut sent = batch(&mut packets).await;
+        assert_eq!(sent.len(), 4);
+        assert_eq!(sent[0], center);
+        assert!(sent[1..].iter().all(|pos| distance(pos, center) == 1));
+        // The next batches are sent once the previous one is acknowledged
+        assert!(sender.awaiting_ack);
+        assert_eq!(sender.pending.len(), 5);
+        sender.acknowledge(&mut conn, f32::NAN).await.unwrap();
+        sent.extend(batch(&mut packets).await);
+        sender.acknowledge(&mut conn, f32::NAN).await.unwrap();
+        sent.extend(batch(&mut packets).await);
+        assert_eq!(sent.len(), 9);
+        assert!(sent
+            .windows(2)
+            .all(|w| distance(&w[0], center) <= distance(&w[1], center)));
+        let view: HashSet<ChunkPos> = sent.iter().copied().collect();
+        assert_eq!(view.len(), 9);
+        assert_eq!(sender.sent, view);
+
+        // Moving the center forgets the chunks that went out of view and sends the new ones
+        sender.acknowledge(&mut conn, 9.0).await.unwrap();
+        let center = ChunkPos::new(2, 0);
+        sender.move_to(&mut conn, center).await.unwrap();
+        expect_center(&mut packets, center).await;
+        let mut forgotten = HashSet::new();
+        for _ in 0..6 {
+            match next(&mut packets).await {
+                ClientboundGamePacket::ForgetLevelChunk(packet) => {
+                    assert!(packet.pos.x < 1);
+                    forgotten.insert(packet.pos);
+                }
+                packet => panic!("Unexpected packet: {packet:?}"),
+            }
+        }
+        assert_eq!(forgotten.len(), 6);
+        // Chunks that are not loaded are sent at their own position, not the empty chunk's
+        let new: HashSet<ChunkPos> = batch(&mut packets).await.into_iter().collect();
+        let expected: HashSet<ChunkPos> = (2..=3)
+            .flat_map(|x| (-1..=1).map(move |z| ChunkPos::new(x, z)))
+            .collect();
+        assert_eq!(new, expected);
+        assert!(sender.pending.is_empty());
+        assert_eq!(sender.sent.len(), 9);
+    }
+}
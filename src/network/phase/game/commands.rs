@@ -0,0 +1,144 @@
+use azalea_protocol::packets::game::clientbound_commands_packet::{BrigadierNodeStub, NodeType};
+
+use crate::config::{
+    self,
+    ty::{CommandConfig, HelpCommand, LobbyCommand, PingCommand, SpawnCommand},
+};
+
+/// A built-in command that is enabled in the configuration.
+#[derive(Debug, Clone, Copy)]
+pub enum Command {
+    Spawn(&'static SpawnCommand),
+    Help(&'static HelpCommand),
+    Lobby(&'static LobbyCommand),
+    Ping(&'static PingCommand),
+}
+
+impl Command {
+    /// Returns the commands that are enabled in the configuration.
+    pub fn enabled() -> Vec<Command> {
+        let commands = &config::get().commands;
+        [
+            commands.spawn.as_ref().map(Command::Spawn),
+            commands.help.as_ref().map(Command::Help),
+            commands.lobby.as_ref().map(Command::Lobby),
+            commands.ping.as_ref().map(Command::Ping),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Looks up the enabled command typed by a player, by its name or one of its aliases.
+    /// None of the commands take arguments, so anything after the first word is ignored.
+    pub fn find(input: &str) -> Option<Command> {
+        let name = input.split_whitespace().next()?;
+        Self::enabled()
+            .into_iter()
+            .find(|command| command.names().any(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    /// Returns the name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Spawn(_) => "spawn",
+            Command::Help(_) => "help",
+            Command::Lobby(_) => "lobby",
+            Command::Ping(_) => "ping",
+        }
+    }
+
+    /// Returns the settings shared by all commands.
+    pub fn config(&self) -> &'static CommandConfig {
+        match self {
+            Command::Spawn(spawn) => &spawn.command,
+            Command::Help(help) => &help.command,
+            Command::Lobby(lobby) => &lobby.command,
+            Command::Ping(ping) => &ping.command,
+        }
+    }
+
+    /// Returns the name of the command followed by its aliases.
+    fn names(&self) -> impl Iterator<Item = &'static str> {
+        let aliases = self.config().aliases.iter().map(String::as_str);
+        std::iter::once(self.name()).chain(aliases)
+    }
+}
+
+/// Builds the Brigadier node graph of the enabled commands, with the root node at index 0.
+/// None of the commands take arguments, so every command is an executable literal below the root,
+/// and its aliases are literals redirecting to it.
+pub fn node_graph() -> Vec<BrigadierNodeStub> {
+    let commands = Command::enabled();
+    let literal = |name: &str, redirect_node| BrigadierNodeStub {
+        is_executable: true,
+        children: Vec::new(),
+        redirect_node,
+        node_type: NodeType::Literal {
+            name: name.to_string(),
+        },
+    };
+    let mut nodes = vec![BrigadierNodeStub {
+        is_executable: false,
+        children: Vec::new(),
+        redirect_node: None,
+        node_type: NodeType::Root,
+    }];
+    for command in &commands {
+        let index = nodes.len() as u32;
+        nodes.push(literal(command.name(), None));
+        for alias in &command.config().aliases {
+            nodes.push(literal(alias, Some(index)));
+        }
+    }
+    nodes[0].children = (1..nodes.len() as u32).collect();
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_name(node: &BrigadierNodeStub) -> &str {
+        match &node.node_type {
+            NodeType::Literal { name } => name,
+            node_type => panic!("Unexpected node type: {node_type:?}"),
+        }
+    }
+
+    #[test]
+    fn aliases_redirect_to_their_command() {
+        config::init_test();
+        let nodes = node_graph();
+        assert!(matches!(nodes[0].node_type, NodeType::Root));
+        assert!(!nodes[0].is_executable);
+        let children: Vec<&str> = nodes[0]
+            .children
+            .iter()
+            .map(|i| literal_name(&nodes[*i as usize]))
+            .collect();
+        // The default configuration enables /spawn, /help (aliased as /?) and /ping
+        assert_eq!(children, ["spawn", "help", "?", "ping"]);
+        for node in &nodes[1..] {
+            assert!(node.is_executable);
+            assert!(node.children.is_empty());
+        }
+        assert_eq!(nodes[3].redirect_node, Some(2));
+        assert_eq!(literal_name(&nodes[2]), "help");
+        assert!(nodes[1].redirect_node.is_none() && nodes[4].redirect_node.is_none());
+    }
+
+    #[test]
+    fn finds_commands_by_their_first_word() {
+        config::init_test();
+        let name = |input: &str| Command::find(input).map(|command| command.name());
+        assert_eq!(name("help"), Some("help"));
+        assert_eq!(name("HELP"), Some("help"));
+        assert_eq!(name("? spawn"), Some("help"));
+        assert_eq!(name("help me please"), Some("help"));
+        assert_eq!(name(" ping "), Some("ping"));
+        assert_eq!(name("lobby"), None);
+        assert_eq!(name("helpme"), None);
+        assert_eq!(name(""), None);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config::{
+        self,
+        ty::{Location, TabListPlayers},
+    },
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+    player::skin::SkinLayersExt,
+};
+
+/// What the client was last told about another player.
+struct TrackedPlayer {
+    entity_id: u32,
+    location: Location,
+    skin_layers: Option<u8>,
+}
+
+/// Keeps track of the other players the client sees, spawning, moving and removing
+/// their entities as they come and go.
+#[derive(Default)]
+pub struct EntityTracker {
+    tracked: HashMap<uuid::Uuid, TrackedPlayer>,
+}
+
+impl EntityTracker {
+    /// Brings the other players the client sees up to date, if players are shown to each other.
+    pub async fn tick(
+        &mut self,
+        conn: &mut GameWriter,
+        server: &AServer,
+        player: &PlayerRef,
+    ) -> network::Result<()> {
+        let Some(config) = &config::get().other_players else {
+            return Ok(());
+        };
+        let (uuid, center) = {
+            let player = player.lock().await;
+            (player.uuid(), player.location())
+        };
+
+        let mut in_range = HashSet::new();
+        for other in server.players() {
+            let (other_uuid, entity_id, location, skin_layers) = {
+                let other = other.lock().await;
+                if other.uuid() == uuid || !other.in_game() {
+                    continue;
+                }
+                let skin_layers = other.skin().map(|skin| skin.layers.to_bits());
+                (
+                    other.uuid(),
+                    other.entity_id(),
+                    other.location(),
+                    skin_layers,
+                )
+            };
+            let (dx, dz) = (location.x() - center.x(), location.z() - center.z());
+            if dx.hypot(dz) > config.tracking_range {
+                continue;
+            }
+            in_range.insert(other_uuid);
+
+            match self.tracked.get_mut(&other_uuid) {
+                // The player reconnected, so its old entity is gone
+                Some(tracked) if tracked.entity_id != entity_id => {
+                    utils::signal_entities_remove(conn, vec![tracked.entity_id]).await?;
+                }
+                Some(tracked) => {
+                    if tracked.location != location {
+                        utils::signal_entity_move(conn, entity_id, location).await?;
+                        tracked.location = location;
+                    }
+                    if tracked.skin_layers != skin_layers {
+                        if let Some(layers) = skin_layers {
+                            utils::signal_entity_skin_layers(conn, entity_id, layers).await?;
+                        }
+                        tracked.skin_layers = skin_layers;
+                    }
+                    continue;
+                }
+                None => {}
+            }
+
+            trace!(player = %other_uuid, "Spawning other player");
+            // The client only spawns players it has a tab list entry for
+            let entry = {
+                let listed = config::get().tab_list.players == TabListPlayers::All;
+                utils::player_info_entry(&*other.lock().await, listed)
+            };
+            utils::signal_player_update(conn, vec![entry]).await?;
+            utils::signal_player_entity_add(conn, entity_id, other_uuid, location).await?;
+            if let Some(layers) = skin_layers {
+                utils::signal_entity_skin_layers(conn, entity_id, layers).await?;
+            }
+            let tracked = TrackedPlayer {
+                entity_id,
+                location,
+                skin_layers,
+            };
+            self.tracked.insert(other_uuid, tracked);
+        }
+
+        let gone: Vec<uuid::Uuid> = self
+            .tracked
+            .keys()
+            .filter(|uuid| !in_range.contains(uuid))
+            .copied()
+            .collect();
+        if gone.is_empty() {
+            return Ok(());
+        }
+        trace!(count = gone.len(), "Removing other players");
+        let entity_ids = gone
+            .iter()
+            .filter_map(|uuid| self.tracked.remove(uuid))
+            .map(|tracked| tracked.entity_id)
+            .collect();
+        utils::signal_entities_remove(conn, entity_ids).await?;
+        // If all players are listed, the tab list entries are removed once the players leave
+        if config::get().tab_list.players != TabListPlayers::All {
+            utils::signal_player_info_remove(conn, gone).await?;
+        }
+        Ok(())
+    }
+}
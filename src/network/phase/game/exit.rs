@@ -0,0 +1,21 @@
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config::ty::ExitAction,
+    network::{self, ext::ConnectionPhaseExt, ConnectionError, GameWriter},
+};
+
+/// Makes the player leave the limbo as configured.
+/// Returns an error ending the session if the player was disconnected.
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn run(conn: &mut GameWriter, action: &ExitAction) -> network::Result<()> {
+    match action {
+        ExitAction::Disconnect { message } => {
+            trace!("Disconnecting player");
+            conn.write_disconnect(message.clone()).await?;
+            Err(ConnectionError::Disconnect(message.clone()))
+        }
+        ExitAction::Connect { server } => utils::signal_proxy_connect(conn, server).await,
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::{Duration, Instant};
+
+use super::{
+    template::{self, Placeholders},
+    utils,
+};
+use crate::{
+    config::{
+        self,
+        ty::{BossBarConfig, SidebarConfig},
+    },
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+};
+
+/// The most lines a sidebar can show.
+const MAX_SIDEBAR_LINES: usize = 15;
+
+/// Keeps the configured messages on the player's screen up to date.
+#[derive(Default)]
+pub struct Hud {
+    /// When the action bar message was last sent, if it has been sent
+    action_bar_sent_at: Option<Instant>,
+    /// When the boss bar was last updated, if it has been created
+    boss_bar_sent_at: Option<Instant>,
+    /// When the tab list header and footer were last sent, if they have been sent
+    tab_list_sent_at: Option<Instant>,
+    /// What the sidebar currently shows, if it has been created
+    sidebar: Option<SidebarState>,
+}
+
+/// The text shown by the sidebar, so only the lines that changed are sent again.
+struct SidebarState {
+    /// When the sidebar was last updated
+    sent_at: Instant,
+    /// The number of players in the limbo when the sidebar was last updated
+    online: i32,
+    title: String,
+    lines: Vec<String>,
+}
+
+impl Hud {
+    /// Shows the messages a player sees when joining.
+    pub async fn show(
+        &mut self,
+        conn: &mut GameWriter,
+        placeholders: &Placeholders,
+    ) -> network::Result<()> {
+        let config = config::get();
+        if let Some(title) = &config.title {
+            let text = placeholders.apply(&title.title);
+            let subtitle = placeholders.apply(&title.subtitle);
+            utils::signal_title(conn, title, text, subtitle).await?;
+        }
+        if let Some(boss_bar) = &config.boss_bar {
+            let (text, progress) = boss_bar_state(boss_bar, placeholders);
+            utils::signal_boss_bar_add(conn, boss_bar, text, progress).await?;
+            self.boss_bar_sent_at = Some(Instant::now());
+        }
+        if let Some(sidebar) = &config.sidebar {
+            self.send_sidebar(conn, sidebar, placeholders).await?;
+        }
+        self.send_tab_list(conn, placeholders).await
+    }
+
+    /// Updates the messages that are due.
+    pub async fn tick(
+        &mut self,
+        conn: &mut GameWriter,
+        server: &AServer,
+        player: &PlayerRef,
+        joined_at: Instant,
+    ) -> network::Result<()> {
+        let config = config::get();
+        let action_bar = config
+            .action_bar
+            .as_ref()
+            .filter(|action_bar| is_due(self.action_bar_sent_at, action_bar.interval));
+        let boss_bar = config
+            .boss_bar
+            .as_ref()
+            .filter(|boss_bar| is_due(self.boss_bar_sent_at, boss_bar.interval));
+        let tab_list = is_due(self.tab_list_sent_at, config.tab_list.interval);
+        let sidebar = config
+            .sidebar
+            .as_ref()
+            .filter(|sidebar| match &self.sidebar {
+                Some(state) => {
+                    is_due(Some(state.sent_at), sidebar.interval)
+                        || state.online != server.get_player_count()
+                }
+                None => true,
+            });
+        if action_bar.is_none() && boss_bar.is_none() && !tab_list && sidebar.is_none() {
+            return Ok(());
+        }
+
+        let placeholders = Placeholders::new(server, player, joined_at).await;
+        if let Some(action_bar) = action_bar {
+            self.action_bar_sent_at = Some(Instant::now());
+            utils::signal_action_bar(conn, placeholders.apply(&action_bar.message)).await?;
+        }
+        if let Some(boss_bar) = boss_bar {
+            self.boss_bar_sent_at = Some(Instant::now());
+            let (text, progress) = boss_bar_state(boss_bar, &placeholders);
+            utils::signal_boss_bar_update(conn, text, progress).await?;
+        }
+        if tab_list {
+            self.send_tab_list(conn, &placeholders).await?;
+        }
+        if let Some(sidebar) = sidebar {
+            self.send_sidebar(conn, sidebar, &placeholders).await?;
+        }
+        Ok(())
+    }
+
+    /// Creates the sidebar, or updates the title and lines that changed since it was last sent.
+    async fn send_sidebar(
+        &mut self,
+        conn: &mut GameWriter,
+        config: &SidebarConfig,
+        placeholders: &Placeholders,
+    ) -> network::Result<()> {
+        let title = placeholders.apply(&config.title);
+        let lines: Vec<String> = config
+            .lines
+            .iter()
+            .take(MAX_SIDEBAR_LINES)
+            .map(|line| placeholders.apply(line))
+            .collect();
+        let previous = self.sidebar.take();
+        if previous.as_ref().map_or(true, |state| state.title != title) {
+            utils::signal_sidebar(conn, title.clone(), previous.is_none()).await?;
+        }
+        for (i, line) in lines.iter().enumerate() {
+            if previous
+                .as_ref()
+                .map_or(true, |state| state.lines[i] != *line)
+            {
+                utils::signal_sidebar_line(conn, i, lines.len(), line.clone()).await?;
+            }
+        }
+        self.sidebar = Some(SidebarState {
+            sent_at: Instant::now(),
+            online: placeholders.online(),
+            title,
+            lines,
+        });
+        Ok(())
+    }
+
+    /// Sends the tab list header and footer, unless both are empty.
+    async fn send_tab_list(
+        &mut self,
+        conn: &mut GameWriter,
+        placeholders: &Placeholders,
+    ) -> network::Result<()> {
+        let tab_list = &config::get().tab_list;
+        self.tab_list_sent_at = Some(Instant::now());
+        if tab_list.header.is_empty() && tab_list.footer.is_empty() {
+            return Ok(());
+        }
+        let header = placeholders.apply(&tab_list.header);
+        let footer = placeholders.apply(&tab_list.footer);
+        utils::signal_tab_list(conn, header, footer).await
+    }
+}
+
+/// Returns whether a message sent at the given time should be sent again.
+fn is_due(sent_at: Option<Instant>, interval: u64) -> bool {
+    sent_at.map_or(true, |sent_at| {
+        sent_at.elapsed() >= Duration::from_secs(interval)
+    })
+}
+
+/// Returns the text and progress of the boss bar.
+fn boss_bar_state(config: &BossBarConfig, placeholders: &Placeholders) -> (String, f32) {
+    let (progress, remaining) = config.progress_at(placeholders.waited());
+    let remaining = remaining.map(template::format_duration).unwrap_or_default();
+    let text = placeholders
+        .apply(&config.text)
+        .replace("{remaining}", &remaining);
+    (text, progress)
+}
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use tracing::*;
+
+use super::{exit, template, utils};
+use crate::{
+    config,
+    network::{self, server::PlayerRef, GameWriter},
+};
+
+/// Time between two updates of the countdown above the hotbar.
+const COUNTDOWN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counts down the time the player may still stay, removing it from the limbo once it is up.
+#[derive(Default)]
+pub struct SessionLimiter {
+    /// The last warning sent (in seconds before the end), if any
+    warned: Option<u64>,
+    /// When the countdown was last shown, if it has been shown
+    countdown_sent_at: Option<Instant>,
+    /// Whether the player has been removed
+    expired: bool,
+}
+
+impl SessionLimiter {
+    pub async fn tick(&mut self, conn: &mut GameWriter, player: &PlayerRef) -> network::Result<()> {
+        let Some(config) = &config::get().session_limit else {
+            return Ok(());
+        };
+        let max_duration = Duration::from_secs(config.max_session_duration);
+        let session_duration = player.lock().await.session_duration();
+        let Some(remaining) = max_duration.checked_sub(session_duration) else {
+            // Sending the player to another server may fail, in which case it stays here
+            if !self.expired {
+                info!("Player reached the maximum session duration");
+                self.expired = true;
+                exit::run(conn, &config.action).await?;
+            }
+            return Ok(());
+        };
+        // Whole seconds, rounded up so the countdown ends at 1
+        let remaining_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        let remaining_text = template::format_duration(Duration::from_secs(remaining_secs));
+
+        let warning = config
+            .warnings
+            .iter()
+            .copied()
+            .filter(|&warning| warning >= remaining_secs)
+            .min();
+        if let Some(warning) = warning {
+            if self.warned.map_or(true, |warned| warning < warned) {
+                debug!(
+                    remaining_secs,
+                    "Warning player about the maximum session duration"
+                );
+                self.warned = Some(warning);
+                let message = config
+                    .warning_message
+                    .replace("{remaining}", &remaining_text);
+                utils::signal_system_message(conn, &message).await?;
+            }
+        }
+
+        let Some(countdown) = &config.countdown_message else {
+            return Ok(());
+        };
+        let countdown_due = self
+            .countdown_sent_at
+            .map_or(true, |sent_at| sent_at.elapsed() >= COUNTDOWN_INTERVAL);
+        if self.warned.is_some() && countdown_due {
+            self.countdown_sent_at = Some(Instant::now());
+            let message = countdown.replace("{remaining}", &remaining_text);
+            utils::signal_action_bar(conn, message).await?;
+        }
+        Ok(())
+    }
+}
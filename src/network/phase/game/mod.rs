@@ -1,83 +1,112 @@
-use azalea_core::position::ChunkPos;
-use azalea_protocol::packets::game::{
-    clientbound_game_event_packet::EventType, ServerboundGamePacket,
-};
-use azalea_world::{Chunk, Section};
+use std::time::Instant;
+
+use azalea_protocol::packets::game::clientbound_game_event_packet::EventType;
+use tokio::sync::mpsc;
 use tracing::*;
 
 use crate::{
-    bail_packet_error, config,
+    config,
     network::{
         self,
-        ext::ConnectionExt,
         server::{AServer, PlayerRef},
     },
+    world,
 };
 
+mod afk;
+mod auto_return;
+mod chat;
+mod chunks;
+mod commands;
+mod entities;
+mod exit;
+mod hud;
+mod limit;
+mod queue;
+mod session;
+mod tab_list;
+mod teleport;
+mod template;
 mod utils;
 
 /// Attempts to handle the game phase.
 #[tracing::instrument(name = "game", skip_all)]
 pub async fn try_handle(
-    mut conn: network::GameConnection,
+    conn: network::GameConnection,
     server: &AServer,
     player: &PlayerRef,
 ) -> network::Result<()> {
     debug!("Handling game phase");
+    let (reader, mut conn) = conn.into_split();
 
-    // Initialize the player entity
-    player.lock().await.init(server);
+    // Initialize the player entity, along with the channel other tasks send packets through
+    let (sender, packets) = mpsc::unbounded_channel();
+    player.lock().await.init(server, sender);
     // Signal game start to the client
-    utils::signal_game_start(&mut conn, &player).await?;
-    // Signal player update to the client
-    utils::signal_player_update(&mut conn, &player).await?;
-    // Signal spawn position to the client
-    utils::signal_spawn_position(&mut conn).await?;
-    // Teleport the player to the spawn location
-    utils::teleport_player(&mut conn, config::get().spawn_location).await?;
-    // Signal player skin layers to the client
-    utils::signal_player_skin_layers(&mut conn, &player).await?;
-    // Signal client to wait for level chunks
-    utils::signal_game_state_change(&mut conn, EventType::WaitForLevelChunks, None).await?;
-    // Signal center chunk to the client
-    utils::signal_center_chunk(&mut conn).await?;
-
-    let mut chunks_test = Vec::new();
-    for x in -6..6 {
-        for z in -6..6 {
-            let mut section = Section::default();
-            // plains biome, this should really be documented somewhere
-            section.biomes.palette = azalea_world::palette::Palette::SingleValue(39);
-            chunks_test.push((
-                ChunkPos::new(x, z),
-                Chunk {
-                    sections: vec![section; 24],
-                    heightmaps: Default::default(),
-                },
-            ));
+    let ambience = server.ambience();
+    utils::signal_game_start(&mut conn, &player, &ambience.game_rules).await?;
+    // Signal the players in the tab list to the client (and the player to the others),
+    // removing the player from the other tab lists again however the game phase ends
+    tab_list::listed(conn, server, player, |mut conn| async move {
+        // Signal spawn position to the client
+        utils::signal_spawn_position(&mut conn).await?;
+        // Signal world border to the client (any shrinking animation starts now)
+        let joined_at = Instant::now();
+        if let Some(border) = &config::get().world.border {
+            utils::signal_world_border(&mut conn, border).await?;
+        }
+        // Signal time of day and weather to the client
+        utils::signal_time(&mut conn, &ambience.time).await?;
+        utils::signal_weather(&mut conn, ambience.weather).await?;
+        // Teleport the player to the spawn location
+        let mut teleports = teleport::Teleports::default();
+        teleports
+            .teleport(&mut conn, config::get().spawn_location)
+            .await?;
+        // Signal player skin layers to the client
+        utils::signal_player_skin_layers(&mut conn, &player).await?;
+        // Signal available commands to the client
+        utils::signal_commands(&mut conn, commands::node_graph()).await?;
+        // Signal client to wait for level chunks
+        utils::signal_game_state_change(&mut conn, EventType::WaitForLevelChunks, None).await?;
+        // Start streaming the chunks around the spawn location to the client
+        let view_distance = player.lock().await.view_distance();
+        let mut chunks = chunks::ChunkSender::new(view_distance as i32);
+        chunks.move_to(&mut conn, world::spawn_chunk()).await?;
+        // Show the join title and other messages, once the first chunks are on their way
+        let mut hud = hud::Hud::default();
+        let placeholders = template::Placeholders::new(server, player, joined_at).await;
+        hud.show(&mut conn, &placeholders).await?;
+        // Add the player to the queue, if the limbo is used as a waiting room
+        if let (Some(queue), Some(config)) = (server.queue(), &config::get().queue) {
+            let player = player.lock().await;
+            queue.join(player.uuid(), queue.priority_of(config, &player));
         }
-    }
-
-    // Signal chunk batch update to the client
-    utils::signal_chunk_batch_update(&mut conn, chunks_test).await?;
 
-    // TODO: send chunks (it works, yay, now load them from the world)
-    // TODO: keepalive (use conn.into_split() and move the writer to a separate task)
-    // TODO: ...profit?
+        // Player has fully joined the game at this point
+        info!("Player has joined the game");
 
-    // Player has fully joined the game at this point
-    info!("Player has joined the game");
+        let session = session::Session::new(
+            conn,
+            server.clone(),
+            player.clone(),
+            chunks,
+            teleports,
+            hud,
+            joined_at,
+        );
+        session.run(reader, packets).await
+    })
+    .await
+}
 
-    loop {
-        match conn.read_timeout(network::ConnectionPhase::Game).await {
-            Ok(ServerboundGamePacket::Pong(_)) => {
-                debug!("Received pong from client");
-                break; // todo
-            }
-            Ok(_) => {} // todo
-            Err(err) => bail_packet_error!(err, "Failed to read configuration packet"),
-        }
+/// Sends the time of day, weather and game rules of the server to every player, once they are reloaded.
+pub async fn broadcast_ambience(server: &AServer) {
+    let ambience = server.ambience();
+    let packets = std::iter::once(utils::time_packet(&ambience.time))
+        .chain(utils::weather_packets(ambience.weather))
+        .chain(utils::game_rule_packets(&ambience.game_rules));
+    for packet in packets {
+        server.broadcast(packet).await;
     }
-
-    Ok(())
 }
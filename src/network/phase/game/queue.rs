@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+use tracing::*;
+
+use super::{exit, template, utils};
+use crate::{
+    config,
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+};
+
+/// Time between two updates of the position shown to the player.
+const POSITION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shows the player its position in the queue, sending it onward once it is released.
+#[derive(Default)]
+pub struct QueueTracker {
+    /// When the position was last shown, if it has been shown
+    shown_at: Option<Instant>,
+}
+
+impl QueueTracker {
+    pub async fn tick(
+        &mut self,
+        conn: &mut GameWriter,
+        server: &AServer,
+        player: &PlayerRef,
+    ) -> network::Result<()> {
+        let (Some(queue), Some(config)) = (server.queue(), &config::get().queue) else {
+            return Ok(());
+        };
+        let uuid = player.lock().await.uuid();
+        if queue.take_released(uuid) {
+            info!("Player was released from the queue");
+            return exit::run(conn, &config.action).await;
+        }
+        if self
+            .shown_at
+            .is_some_and(|shown_at| shown_at.elapsed() < POSITION_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.shown_at = Some(Instant::now());
+        let Some((position, size)) = queue.position(uuid) else {
+            return Ok(());
+        };
+        let eta = match queue.estimated_wait(position) {
+            Some(wait) => template::format_duration(wait),
+            None => config.unknown_eta.clone(),
+        };
+        let message = config
+            .message
+            .replace("{position}", &position.to_string())
+            .replace("{size}", &size.to_string())
+            .replace("{eta}", &eta);
+        utils::signal_action_bar(conn, message).await
+    }
+}
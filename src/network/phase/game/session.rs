@@ -0,0 +1,344 @@
+use std::time::{Duration, Instant};
+
+use azalea_core::position::ChunkPos;
+use azalea_protocol::{
+    packets::game::{ClientboundGamePacket, ServerboundGamePacket},
+    read::ReadPacketError,
+};
+use tokio::sync::mpsc;
+use tracing::*;
+
+use super::{
+    afk::AfkTracker, auto_return::AutoReturn, chat::Chat, chunks::ChunkSender, commands,
+    entities::EntityTracker, hud::Hud, limit::SessionLimiter, queue::QueueTracker,
+    teleport::Teleports, utils,
+};
+use crate::{
+    bail_packet_error,
+    config::{self, ty::Location},
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        ConnectionError, ConnectionPhase, GameReader, GameWriter,
+    },
+};
+
+/// Time between two ticks of the session.
+const TICK_INTERVAL: Duration = Duration::from_millis(50);
+/// Time between two keep alive packets (as in vanilla).
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Amount of packets that are read ahead of the session handling them.
+const PACKET_BUFFER: usize = 64;
+
+/// A player that has joined the game.
+pub struct Session {
+    conn: GameWriter,
+    server: AServer,
+    player: PlayerRef,
+    chunks: ChunkSender,
+    teleports: Teleports,
+    chat: Chat,
+    hud: Hud,
+    entities: EntityTracker,
+    afk: AfkTracker,
+    limit: SessionLimiter,
+    queue: QueueTracker,
+    auto_return: AutoReturn,
+    /// When the player joined (and any world border animation started)
+    joined_at: Instant,
+    /// When the last keep alive was sent, if any
+    keep_alive_sent_at: Option<Instant>,
+    /// The ID of the last keep alive, if the client has not answered it yet
+    keep_alive_pending: Option<u64>,
+}
+
+impl Session {
+    pub fn new(
+        conn: GameWriter,
+        server: AServer,
+        player: PlayerRef,
+        chunks: ChunkSender,
+        teleports: Teleports,
+        hud: Hud,
+        joined_at: Instant,
+    ) -> Self {
+        Self {
+            conn,
+            server,
+            player,
+            chunks,
+            teleports,
+            chat: Chat::default(),
+            hud,
+            entities: EntityTracker::default(),
+            afk: AfkTracker::default(),
+            limit: SessionLimiter::default(),
+            queue: QueueTracker::default(),
+            auto_return: AutoReturn::default(),
+            joined_at,
+            keep_alive_sent_at: None,
+            keep_alive_pending: None,
+        }
+    }
+
+    /// Handles packets from the client and from other tasks, and ticks the session
+    /// until the connection is closed.
+    pub async fn run(
+        mut self,
+        mut reader: GameReader,
+        mut outgoing: mpsc::UnboundedReceiver<ClientboundGamePacket>,
+    ) -> network::Result<()> {
+        // Reading a packet is not cancellation-safe, so packets are read in a separate task
+        let (sender, mut packets) = mpsc::channel(PACKET_BUFFER);
+        let reader = tokio::spawn(async move {
+            loop {
+                let packet = reader.read().await;
+                let failed = packet.is_err();
+                if sender.send(packet).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        let result = self.handle(&mut packets, &mut outgoing).await;
+        reader.abort();
+        result
+    }
+
+    async fn handle(
+        &mut self,
+        packets: &mut mpsc::Receiver<Result<ServerboundGamePacket, Box<ReadPacketError>>>,
+        outgoing: &mut mpsc::UnboundedReceiver<ClientboundGamePacket>,
+    ) -> network::Result<()> {
+        let mut ticker = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            tokio::select! {
+                packet = packets.recv() => match packet {
+                    Some(Ok(packet)) => self.handle_packet(packet).await?,
+                    Some(Err(err)) => {
+                        let err = ConnectionError::from(err);
+                        bail_packet_error!(err, "Failed to read game packet")
+                    }
+                    None => return Ok(()),
+                },
+                Some(packet) = outgoing.recv() => self.conn.write(packet).await?,
+                _ = ticker.tick() => self.tick().await?,
+            }
+        }
+    }
+
+    /// Handles a packet from the client.
+    async fn handle_packet(&mut self, packet: ServerboundGamePacket) -> network::Result<()> {
+        match packet {
+            ServerboundGamePacket::Pong(_) => {
+                // The limbo never sends pings, so any pong is simply ignored
+                trace!("Received pong from client");
+            }
+            ServerboundGamePacket::KeepAlive(packet) => match self.keep_alive_sent_at {
+                Some(sent_at) if self.keep_alive_pending == Some(packet.id) => {
+                    self.keep_alive_pending = None;
+                    self.player.lock().await.set_latency(sent_at.elapsed());
+                }
+                _ => {
+                    debug!(id = packet.id, "Received unexpected keep alive from client");
+                }
+            },
+            ServerboundGamePacket::AcceptTeleportation(packet) => {
+                if let Some(location) = self.teleports.confirm(packet.id) {
+                    self.player.lock().await.set_location(location);
+                    let center = chunk_at(location.x(), location.z());
+                    self.chunks.move_to(&mut self.conn, center).await?;
+                }
+            }
+            // Like vanilla, movement is ignored until the client confirms the last teleport
+            ServerboundGamePacket::MovePlayerPos(_)
+            | ServerboundGamePacket::MovePlayerPosRot(_)
+            | ServerboundGamePacket::MovePlayerRot(_)
+                if self.teleports.is_pending() =>
+            {
+                trace!("Ignoring movement until teleport is confirmed");
+            }
+            ServerboundGamePacket::MovePlayerPos(packet) => {
+                let location = self.player.lock().await.location();
+                let location = location.with_position(packet.x, packet.y, packet.z);
+                self.handle_move(location).await?;
+            }
+            ServerboundGamePacket::MovePlayerPosRot(packet) => {
+                let location = self.player.lock().await.location();
+                let location = location
+                    .with_position(packet.x, packet.y, packet.z)
+                    .with_rotation(packet.y_rot, packet.x_rot);
+                self.handle_move(location).await?;
+            }
+            ServerboundGamePacket::MovePlayerRot(packet) => {
+                let mut player = self.player.lock().await;
+                let location = player.location().with_rotation(packet.y_rot, packet.x_rot);
+                if location != player.location() {
+                    player.mark_active();
+                }
+                player.set_location(location);
+            }
+            ServerboundGamePacket::ClientInformation(packet) => {
+                debug!("Received client information from client");
+                let view_distance = {
+                    let mut player = self.player.lock().await;
+                    player.set_skin_layers(packet.information.model_customization);
+                    player.set_client_view_distance(packet.information.view_distance as u32);
+                    player.view_distance()
+                };
+                self.chunks
+                    .set_radius(&mut self.conn, view_distance as i32)
+                    .await?;
+            }
+            ServerboundGamePacket::Chat(packet) => {
+                self.player.lock().await.mark_active();
+                let (conn, server, player) = (&mut self.conn, &self.server, &self.player);
+                self.chat
+                    .handle(conn, server, player, packet.message)
+                    .await?;
+            }
+            ServerboundGamePacket::ChatCommand(packet) => {
+                self.player.lock().await.mark_active();
+                self.handle_command(&packet.command).await?;
+            }
+            ServerboundGamePacket::ChunkBatchReceived(packet) => {
+                self.chunks
+                    .acknowledge(&mut self.conn, packet.desired_chunks_per_tick)
+                    .await?;
+            }
+            ServerboundGamePacket::Swing(_)
+            | ServerboundGamePacket::UseItem(_)
+            | ServerboundGamePacket::UseItemOn(_)
+            | ServerboundGamePacket::Interact(_)
+            | ServerboundGamePacket::PlayerAction(_)
+            | ServerboundGamePacket::PlayerCommand(_)
+            | ServerboundGamePacket::SetCarriedItem(_) => {
+                self.player.lock().await.mark_active();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn tick(&mut self) -> network::Result<()> {
+        self.teleports.tick(&mut self.conn).await?;
+        let (conn, server, player) = (&mut self.conn, &self.server, &self.player);
+        self.hud.tick(conn, server, player, self.joined_at).await?;
+        self.entities.tick(conn, server, player).await?;
+        self.afk.tick(conn, player).await?;
+        self.limit.tick(conn, player).await?;
+        self.queue.tick(conn, server, player).await?;
+        self.auto_return
+            .tick(conn, server, player, self.joined_at)
+            .await?;
+        self.tick_keep_alive().await
+    }
+
+    /// Sends a keep alive every few seconds, timing out if the client does not answer it.
+    /// The first one is sent right away, so the latency of the player is known early.
+    async fn tick_keep_alive(&mut self) -> network::Result<()> {
+        let elapsed = self.keep_alive_sent_at.map(|sent_at| sent_at.elapsed());
+        if self.keep_alive_pending.is_some() {
+            if elapsed >= Some(unsafe { network::ext::READ_TIMEOUT }) {
+                return Err(ConnectionError::ReadTimeout(ConnectionPhase::Game));
+            }
+            return Ok(());
+        }
+        if elapsed.map_or(true, |elapsed| elapsed >= KEEP_ALIVE_INTERVAL) {
+            let id = self.joined_at.elapsed().as_millis() as u64;
+            utils::signal_keep_alive(&mut self.conn, id).await?;
+            self.keep_alive_pending = Some(id);
+            self.keep_alive_sent_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Runs a command typed by the player (without the leading slash).
+    async fn handle_command(&mut self, input: &str) -> network::Result<()> {
+        info!(command = input, "Player used command");
+        let Some(command) = commands::Command::find(input) else {
+            let message = &config::get().commands.unknown_message;
+            return utils::signal_system_message(&mut self.conn, message).await;
+        };
+        match command {
+            commands::Command::Spawn(spawn) => {
+                self.return_to_spawn().await?;
+                if let Some(message) = &spawn.message {
+                    utils::signal_system_message(&mut self.conn, message).await?;
+                }
+            }
+            commands::Command::Help(help) => {
+                let mut message = help.header.clone();
+                for command in commands::Command::enabled() {
+                    let line = help
+                        .format
+                        .replace("{command}", command.name())
+                        .replace("{description}", &command.config().description);
+                    message.push('\n');
+                    message.push_str(&line);
+                }
+                utils::signal_system_message(&mut self.conn, &message).await?;
+            }
+            commands::Command::Lobby(lobby) => {
+                if let Some(message) = &lobby.message {
+                    utils::signal_system_message(&mut self.conn, message).await?;
+                }
+                utils::signal_proxy_connect(&mut self.conn, &lobby.server).await?;
+            }
+            commands::Command::Ping(ping) => {
+                let message = match self.player.lock().await.latency() {
+                    Some(latency) => ping
+                        .message
+                        .replace("{ping}", &latency.as_millis().to_string()),
+                    None => ping.unknown_message.clone(),
+                };
+                utils::signal_system_message(&mut self.conn, &message).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the player moving to the given location,
+    /// sending them back to the spawn location if they are not allowed to be there.
+    async fn handle_move(&mut self, location: Location) -> network::Result<()> {
+        {
+            let mut player = self.player.lock().await;
+            if location != player.location() {
+                player.mark_active();
+            }
+        }
+        let config = config::get();
+        let (x, z) = (location.x(), location.z());
+        if let Some(border) = &config.world.border {
+            if !border.contains(x, z, self.joined_at.elapsed()) {
+                debug!(%location, "Player crossed the world border");
+                return self.return_to_spawn().await;
+            }
+        }
+        if let Some(protection) = &config.fall_protection {
+            if protection.should_return(&location) {
+                debug!(%location, "Player fell out of the world");
+                self.return_to_spawn().await?;
+                if let Some(message) = &protection.message {
+                    utils::signal_system_message(&mut self.conn, message).await?;
+                }
+                if let Some(sound) = &protection.sound {
+                    utils::signal_sound(&mut self.conn, sound, config.spawn_location).await?;
+                }
+                return Ok(());
+            }
+        }
+        self.player.lock().await.set_location(location);
+        self.chunks.move_to(&mut self.conn, chunk_at(x, z)).await
+    }
+
+    /// Teleports the player back to the spawn location.
+    async fn return_to_spawn(&mut self) -> network::Result<()> {
+        let spawn = config::get().spawn_location;
+        self.teleports.teleport(&mut self.conn, spawn).await
+    }
+}
+
+/// Returns the position of the chunk containing the given coordinates.
+fn chunk_at(x: f64, z: f64) -> ChunkPos {
+    ChunkPos::new((x.floor() as i32) >> 4, (z.floor() as i32) >> 4)
+}
@@ -0,0 +1,131 @@
+use std::future::Future;
+
+use azalea_protocol::packets::game::clientbound_player_info_remove_packet::ClientboundPlayerInfoRemovePacket;
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config::{self, ty::TabListPlayers},
+    network::{
+        self,
+        server::{AServer, PlayerRef},
+        GameWriter,
+    },
+};
+
+/// Adds the joining player to its own tab list and, if all players are listed,
+/// adds it to the tab lists of the other players and the other players to its tab list.
+#[tracing::instrument(level = "trace", skip_all, err)]
+pub async fn join(
+    conn: &mut GameWriter,
+    server: &AServer,
+    player: &PlayerRef,
+) -> network::Result<()> {
+    let players = config::get().tab_list.players;
+    let (uuid, entry) = {
+        let player = player.lock().await;
+        let listed = players != TabListPlayers::Nobody;
+        (player.uuid(), utils::player_info_entry(&player, listed))
+    };
+    let mut entries = vec![entry.clone()];
+    if players == TabListPlayers::All {
+        trace!("Adding player to the tab lists of other players");
+        for other in server.players() {
+            let other = other.lock().await;
+            if other.uuid() != uuid && other.in_game() {
+                other.send(utils::player_info_add(vec![entry.clone()]));
+                entries.push(utils::player_info_entry(&other, true));
+            }
+        }
+    }
+    utils::signal_player_update(conn, entries).await
+}
+
+/// Adds the player to the tab lists with [`join`], then runs the rest of the game phase.
+/// Whether it ends normally or with an error (even while joining), the player is removed from
+/// the tab lists of the other players with [`leave`] afterwards, so no entry is left behind.
+pub async fn listed<F, Fut>(
+    mut conn: GameWriter,
+    server: &AServer,
+    player: &PlayerRef,
+    rest: F,
+) -> network::Result<()>
+where
+    F: FnOnce(GameWriter) -> Fut,
+    Fut: Future<Output = network::Result<()>>,
+{
+    let result = match join(&mut conn, server, player).await {
+        Ok(()) => rest(conn).await,
+        Err(err) => Err(err),
+    };
+    leave(server, player).await;
+    result
+}
+
+/// Removes the leaving player from the tab lists of the other players,
+/// unless it reconnected and its new connection is listed instead.
+pub async fn leave(server: &AServer, player: &PlayerRef) {
+    if config::get().tab_list.players != TabListPlayers::All {
+        return;
+    }
+    let uuid = player.lock().await.uuid();
+    if server.is_replaced(&uuid, player) {
+        return;
+    }
+    trace!("Removing player from the tab lists of other players");
+    let packet = ClientboundPlayerInfoRemovePacket {
+        profile_ids: vec![uuid],
+    };
+    server.broadcast(packet.get()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_protocol::packets::game::{
+        clientbound_game_event_packet::EventType, ClientboundGamePacket,
+    };
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{network::server::Server, player::Player};
+
+    fn add_player(server: &AServer, name: &str, id: u128) -> PlayerRef {
+        let addr = "127.0.0.1:25565".parse().unwrap();
+        let uuid = uuid::Uuid::from_u128(id);
+        let player = Player::new(addr, name.into(), uuid, None, Default::default());
+        server.add_player(player).unwrap()
+    }
+
+    #[tokio::test]
+    async fn player_is_removed_when_the_connection_fails() {
+        config::init_test();
+        let server = Server::bind_test().await;
+        let observer = add_player(&server, "Observer", 1);
+        let (sender, mut packets) = mpsc::unbounded_channel();
+        observer.lock().await.init(&server, sender);
+        let player = add_player(&server, "Player", 2);
+        let uuid = player.lock().await.uuid();
+
+        let (conn, _client) = utils::test_connection().await;
+        let result = listed(conn, &server, &player, |mut conn| async move {
+            // The connection breaks right after the player joined the tab lists
+            conn.shutdown().await?;
+            utils::signal_game_state_change(&mut conn, EventType::WaitForLevelChunks, None).await
+        })
+        .await;
+        assert!(result.is_err());
+
+        match packets.recv().await.unwrap() {
+            ClientboundGamePacket::PlayerInfoUpdate(packet) => {
+                assert_eq!(packet.entries[0].profile.uuid, uuid)
+            }
+            packet => panic!("Unexpected packet: {packet:?}"),
+        }
+        match packets.recv().await.unwrap() {
+            ClientboundGamePacket::PlayerInfoRemove(packet) => {
+                assert_eq!(packet.profile_ids, [uuid])
+            }
+            packet => panic!("Unexpected packet: {packet:?}"),
+        }
+    }
+}
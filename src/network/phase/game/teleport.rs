@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use tracing::*;
+
+use super::utils;
+use crate::{
+    config::ty::Location,
+    network::{self, GameWriter},
+};
+
+/// Time after which an unconfirmed teleport is sent again (20 ticks, as in vanilla).
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A teleport the client has not confirmed yet.
+struct PendingTeleport {
+    id: u32,
+    location: Location,
+    sent_at: Instant,
+}
+
+/// Keeps track of the teleports sent to a client and their confirmations.
+#[derive(Default)]
+pub struct Teleports {
+    /// The ID of the most recent teleport
+    last_id: u32,
+    pending: Option<PendingTeleport>,
+}
+
+impl Teleports {
+    /// Teleports the client to a location.
+    /// Until the client confirms it, its movement should be ignored.
+    pub async fn teleport(
+        &mut self,
+        conn: &mut GameWriter,
+        location: Location,
+    ) -> network::Result<()> {
+        self.last_id = self.last_id.wrapping_add(1);
+        self.pending = Some(PendingTeleport {
+            id: self.last_id,
+            location,
+            sent_at: Instant::now(),
+        });
+        utils::teleport_player(conn, location, self.last_id).await
+    }
+
+    /// Returns whether a teleport has not been confirmed yet.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Handles the client confirming a teleport,
+    /// returning the location it was teleported to if it is the one we are waiting for.
+    pub fn confirm(&mut self, id: u32) -> Option<Location> {
+        match &self.pending {
+            Some(pending) if pending.id == id => {
+                trace!(id, "Client confirmed teleport");
+                self.pending.take().map(|pending| pending.location)
+            }
+            _ => {
+                debug!(id, "Client confirmed unexpected teleport");
+                None
+            }
+        }
+    }
+
+    /// Sends the pending teleport again if the client has not confirmed it in time.
+    pub async fn tick(&mut self, conn: &mut GameWriter) -> network::Result<()> {
+        let location = match &self.pending {
+            Some(pending) if pending.sent_at.elapsed() >= CONFIRM_TIMEOUT => pending.location,
+            _ => return Ok(()),
+        };
+        debug!("Client did not confirm teleport in time, sending it again");
+        self.teleport(conn, location).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_protocol::packets::game::ClientboundGamePacket;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn only_the_last_teleport_is_confirmed() {
+        let (mut conn, mut packets) = utils::test_connection().await;
+        let mut teleports = Teleports::default();
+        let first = Location::new(0.5, 64.0, 0.5, 0.0, 0.0);
+        let second = Location::new(100.5, 70.0, -20.5, 90.0, 0.0);
+        teleports.teleport(&mut conn, first).await.unwrap();
+        teleports.teleport(&mut conn, second).await.unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            match packets.recv().await.unwrap() {
+                ClientboundGamePacket::PlayerPosition(packet) => ids.push(packet.id),
+                packet => panic!("Unexpected packet: {packet:?}"),
+            }
+        }
+        assert_eq!(ids, [1, 2]);
+
+        // The session ignores movement as long as a teleport is pending,
+        // so confirming an outdated or unknown teleport must not end it
+        assert!(teleports.is_pending());
+        assert_eq!(teleports.confirm(1), None);
+        assert_eq!(teleports.confirm(42), None);
+        assert!(teleports.is_pending());
+        assert_eq!(teleports.confirm(2), Some(second));
+        assert!(!teleports.is_pending());
+        assert_eq!(teleports.confirm(2), None);
+    }
+
+    #[tokio::test]
+    async fn unconfirmed_teleport_is_sent_again() {
+        let (mut conn, mut packets) = utils::test_connection().await;
+        let mut teleports = Teleports::default();
+        let location = Location::new(0.5, 64.0, 0.5, 0.0, 0.0);
+        teleports.teleport(&mut conn, location).await.unwrap();
+        // Not sent again before the timeout
+        teleports.tick(&mut conn).await.unwrap();
+        assert_eq!(teleports.last_id, 1);
+
+        if let Some(pending) = &mut teleports.pending {
+            pending.sent_at -= CONFIRM_TIMEOUT;
+        }
+        teleports.tick(&mut conn).await.unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            match packets.recv().await.unwrap() {
+                ClientboundGamePacket::PlayerPosition(packet) => ids.push(packet.id),
+                packet => panic!("Unexpected packet: {packet:?}"),
+            }
+        }
+        assert_eq!(ids, [1, 2]);
+        // The client confirms the first one before receiving the second one
+        assert_eq!(teleports.confirm(1), None);
+        assert!(teleports.is_pending());
+        assert_eq!(teleports.confirm(2), Some(location));
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    config,
+    network::server::{AServer, PlayerRef},
+};
+
+/// Values that can be inserted into configurable messages.
+pub struct Placeholders {
+    /// The name of the player
+    player: String,
+    /// The number of players in the limbo
+    online: i32,
+    /// The time the player has spent in the limbo
+    waited: Duration,
+}
+
+impl Placeholders {
+    pub async fn new(server: &AServer, player: &PlayerRef, joined_at: Instant) -> Self {
+        Self {
+            player: player.lock().await.name().to_string(),
+            online: server.get_player_count(),
+            waited: joined_at.elapsed(),
+        }
+    }
+
+    /// Returns the number of players in the limbo.
+    pub fn online(&self) -> i32 {
+        self.online
+    }
+
+    /// Returns the time the player has spent in the limbo.
+    pub fn waited(&self) -> Duration {
+        self.waited
+    }
+
+    /// Replaces the placeholders in a message with their values.
+    pub fn apply(&self, template: &str) -> String {
+        template
+            .replace("{player}", &self.player)
+            .replace("{online}", &self.online.to_string())
+            .replace("{max_players}", &config::get().max_players.to_string())
+            .replace("{waited}", &format_duration(self.waited))
+    }
+}
+
+/// Formats a duration with its hours, minutes and seconds, leaving out leading zero units
+/// (e.g. `5s`, `2m 5s` or `1h 0m 5s`).
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds}s"),
+        _ => format!("{hours}h {minutes}m {seconds}s"),
+    }
+}
@@ -8,47 +8,81 @@ use azalea_entity::{EntityDataItem, EntityDataValue, EntityMetadataItems};
 use azalea_protocol::packets::{
     common::CommonPlayerSpawnInfo,
     game::{
+        clientbound_add_entity_packet::ClientboundAddEntityPacket,
+        clientbound_boss_event_packet::{
+            self, AddOperation, ClientboundBossEventPacket, Operation, Properties, Style,
+        },
         clientbound_chunk_batch_finished_packet::ClientboundChunkBatchFinishedPacket,
         clientbound_chunk_batch_start_packet::ClientboundChunkBatchStartPacket,
+        clientbound_commands_packet::{BrigadierNodeStub, ClientboundCommandsPacket},
+        clientbound_custom_payload_packet::ClientboundCustomPayloadPacket,
+        clientbound_forget_level_chunk_packet::ClientboundForgetLevelChunkPacket,
         clientbound_game_event_packet::{ClientboundGameEventPacket, EventType},
-        clientbound_level_chunk_with_light_packet::{
-            ClientboundLevelChunkPacketData, ClientboundLevelChunkWithLightPacket,
-        },
-        clientbound_light_update_packet::ClientboundLightUpdatePacketData,
+        clientbound_initialize_border_packet::ClientboundInitializeBorderPacket,
+        clientbound_keep_alive_packet::ClientboundKeepAlivePacket,
         clientbound_login_packet::ClientboundLoginPacket,
         clientbound_player_abilities_packet::{
             ClientboundPlayerAbilitiesPacket, PlayerAbilitiesFlags,
         },
+        clientbound_player_info_remove_packet::ClientboundPlayerInfoRemovePacket,
         clientbound_player_info_update_packet::{
             ActionEnumSet, ClientboundPlayerInfoUpdatePacket, PlayerInfoEntry,
         },
         clientbound_player_position_packet::{ClientboundPlayerPositionPacket, RelativeMovements},
+        clientbound_remove_entities_packet::ClientboundRemoveEntitiesPacket,
+        clientbound_rotate_head_packet::ClientboundRotateHeadPacket,
+        clientbound_set_action_bar_text_packet::ClientboundSetActionBarTextPacket,
         clientbound_set_chunk_cache_center_packet::ClientboundSetChunkCacheCenterPacket,
         clientbound_set_default_spawn_position_packet::ClientboundSetDefaultSpawnPositionPacket,
+        clientbound_set_display_objective_packet::{
+            ClientboundSetDisplayObjectivePacket, DisplaySlot,
+        },
         clientbound_set_entity_data_packet::ClientboundSetEntityDataPacket,
+        clientbound_set_objective_packet::{
+            ClientboundSetObjectivePacket, DisplayInfo, Method, NumberFormat, RenderType,
+        },
+        clientbound_set_score_packet::ClientboundSetScorePacket,
+        clientbound_set_subtitle_text_packet::ClientboundSetSubtitleTextPacket,
+        clientbound_set_time_packet::ClientboundSetTimePacket,
+        clientbound_set_title_text_packet::ClientboundSetTitleTextPacket,
+        clientbound_set_titles_animation_packet::ClientboundSetTitlesAnimationPacket,
+        clientbound_sound_packet::{ClientboundSoundPacket, CustomSound, SoundSource},
+        clientbound_system_chat_packet::ClientboundSystemChatPacket,
+        clientbound_tab_list_packet::ClientboundTabListPacket,
+        clientbound_teleport_entity_packet::ClientboundTeleportEntityPacket,
+        ClientboundGamePacket,
     },
 };
-use azalea_world::Chunk;
-use simdnbt::{
-    owned::{Nbt, NbtCompound, NbtTag},
-    Serialize,
-};
+use azalea_registry::{CustomRegistry, EntityKind};
 use tracing::*;
 
 use crate::{
-    config::{self, ty::Location},
-    network::{
+    config::{
         self,
-        server::{constants, PlayerRef},
-        GameConnection,
+        ty::{
+            BossBarColor, BossBarConfig, BossBarStyle, GameRules, Location, TimeConfig,
+            TitleConfig, Weather, WorldBorder,
+        },
     },
-    player::skin::SkinLayersExt,
+    network::{self, cache, ext::WriteConnectionExt, server::PlayerRef, GameWriter},
+    player::{skin::SkinLayersExt, Player},
+    world,
 };
 
+/// The largest world border size the client accepts.
+const MAX_BORDER_SIZE: u32 = 29_999_984;
+/// The ID of the boss bar, which is the only one each player sees.
+const BOSS_BAR_ID: uuid::Uuid = uuid::Uuid::from_u128(1);
+/// The name of the sidebar objective, which is the only one each player sees.
+const SIDEBAR_OBJECTIVE: &str = "limbo_sidebar";
+/// The plugin channel BungeeCord (and Velocity, if enabled) listens on for messages from servers.
+const BUNGEECORD_CHANNEL: &str = "bungeecord:main";
+
 #[tracing::instrument(level = "trace", skip_all, err)]
 pub async fn signal_game_start(
-    conn: &mut GameConnection,
+    conn: &mut GameWriter,
     player: &PlayerRef,
+    game_rules: &GameRules,
 ) -> network::Result<()> {
     trace!("Signaling game start to client");
     let config = config::get();
@@ -57,16 +91,16 @@ pub async fn signal_game_start(
         ClientboundLoginPacket {
             player_id,
             hardcore: false,
-            levels: vec![ResourceLocation::new("minecraft:world")],
+            levels: vec![ResourceLocation::new(world::DIMENSION_NAME)],
             max_players: config.max_players,
-            chunk_radius: constants::VIEW_DISTANCE,
-            simulation_distance: constants::SIMULATION_DISTANCE,
+            chunk_radius: config.view_distance,
+            simulation_distance: config.simulation_distance,
             reduced_debug_info: !cfg!(debug_assertions),
-            show_death_screen: true,
-            do_limited_crafting: false,
+            show_death_screen: !game_rules.immediate_respawn,
+            do_limited_crafting: game_rules.limited_crafting,
             common: CommonPlayerSpawnInfo {
-                dimension_type: ResourceLocation::new("minecraft:overworld"),
-                dimension: ResourceLocation::new("minecraft:world"),
+                dimension_type: ResourceLocation::new(&config.world.dimension_type),
+                dimension: ResourceLocation::new(world::DIMENSION_NAME),
                 seed: 0,
                 game_type: *config.default_gamemode,
                 previous_game_type: OptionalGameType(None),
@@ -82,41 +116,51 @@ pub async fn signal_game_start(
     Ok(())
 }
 
-#[tracing::instrument(level = "trace", skip_all, err)]
+#[tracing::instrument(level = "trace", skip_all, fields(len = entries.len()), err)]
 pub async fn signal_player_update(
-    conn: &mut GameConnection,
-    player: &PlayerRef,
+    conn: &mut GameWriter,
+    entries: Vec<PlayerInfoEntry>,
 ) -> network::Result<()> {
     trace!("Signaling player update to client");
-    let config = config::get();
-    let profile = player.lock().await.game_profile();
-    conn.write(
-        ClientboundPlayerInfoUpdatePacket {
-            actions: ActionEnumSet {
-                add_player: true,
-                initialize_chat: false,
-                update_game_mode: true,
-                update_listed: true,
-                update_latency: true,
-                update_display_name: true,
-            },
-            entries: vec![PlayerInfoEntry {
-                profile,
-                listed: true,
-                latency: 0,
-                game_mode: *config.default_gamemode,
-                display_name: None,
-                chat_session: None,
-            }],
-        }
-        .get(),
-    )
-    .await?;
+    conn.write(player_info_add(entries)).await?;
     Ok(())
 }
 
+/// Builds the packet adding players to the tab list.
+pub fn player_info_add(entries: Vec<PlayerInfoEntry>) -> ClientboundGamePacket {
+    ClientboundPlayerInfoUpdatePacket {
+        actions: ActionEnumSet {
+            add_player: true,
+            initialize_chat: false,
+            update_game_mode: true,
+            update_listed: true,
+            update_latency: true,
+            update_display_name: true,
+        },
+        entries,
+    }
+    .get()
+}
+
+/// Builds the tab list entry of a player.
+/// Clients need the entries of the players they see even when they are not listed
+/// (e.g. for their skin).
+pub fn player_info_entry(player: &Player, listed: bool) -> PlayerInfoEntry {
+    let config = config::get();
+    PlayerInfoEntry {
+        profile: player.game_profile(),
+        listed,
+        latency: player
+            .latency()
+            .map_or(0, |latency| latency.as_millis() as i32),
+        game_mode: *config.default_gamemode,
+        display_name: None,
+        chat_session: None,
+    }
+}
+
 #[tracing::instrument(level = "trace", skip_all, err)]
-pub async fn signal_player_abilities(conn: &mut GameConnection) -> network::Result<()> {
+pub async fn signal_player_abilities(conn: &mut GameWriter) -> network::Result<()> {
     trace!("Signaling player update to client");
     let config = config::get();
     let creative_mode = *config.default_gamemode == GameMode::Creative;
@@ -139,24 +183,86 @@ pub async fn signal_player_abilities(conn: &mut GameConnection) -> network::Resu
 
 #[tracing::instrument(level = "trace", skip(conn), err)]
 pub async fn signal_game_state_change(
-    conn: &mut GameConnection,
+    conn: &mut GameWriter,
     event: EventType,
     param: Option<f32>,
 ) -> network::Result<()> {
     trace!("Signaling game state change to client");
-    conn.write(
-        ClientboundGameEventPacket {
-            event,
-            param: param.unwrap_or_default(),
-        }
-        .get(),
-    )
-    .await?;
+    conn.write(game_event(event, param)).await?;
+    Ok(())
+}
+
+/// Builds a game state change packet.
+fn game_event(event: EventType, param: Option<f32>) -> ClientboundGamePacket {
+    ClientboundGameEventPacket {
+        event,
+        param: param.unwrap_or_default(),
+    }
+    .get()
+}
+
+#[tracing::instrument(level = "trace", skip_all, err)]
+pub async fn signal_time(conn: &mut GameWriter, time: &TimeConfig) -> network::Result<()> {
+    trace!("Signaling time to client");
+    conn.write(time_packet(time)).await?;
+    Ok(())
+}
+
+/// Builds the packet setting the time of day.
+pub fn time_packet(time: &TimeConfig) -> ClientboundGamePacket {
+    // A negative time of day stops the client from advancing it
+    let day_time = match (time.daylight_cycle, time.time_of_day as i64) {
+        (true, day_time) => day_time,
+        (false, 0) => -1,
+        (false, day_time) => -day_time,
+    };
+    ClientboundSetTimePacket {
+        game_time: 0,
+        day_time: day_time as u64,
+    }
+    .get()
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_weather(conn: &mut GameWriter, weather: Weather) -> network::Result<()> {
+    trace!("Signaling weather to client");
+    for packet in weather_packets(weather) {
+        conn.write(packet).await?;
+    }
     Ok(())
 }
 
+/// Builds the packets setting the weather, replacing any weather the client already has.
+pub fn weather_packets(weather: Weather) -> Vec<ClientboundGamePacket> {
+    let (event, rain, thunder) = match weather {
+        Weather::Clear => (EventType::StopRaining, 0.0, 0.0),
+        Weather::Rain => (EventType::StartRaining, 1.0, 0.0),
+        Weather::Thunder => (EventType::StartRaining, 1.0, 1.0),
+    };
+    vec![
+        game_event(event, None),
+        game_event(EventType::RainLevelChange, Some(rain)),
+        game_event(EventType::ThunderLevelChange, Some(thunder)),
+    ]
+}
+
+/// Builds the packets setting the client-visible game rules, which are otherwise sent at join.
+pub fn game_rule_packets(game_rules: &GameRules) -> Vec<ClientboundGamePacket> {
+    let flag = |enabled: bool| Some(if enabled { 1.0 } else { 0.0 });
+    vec![
+        game_event(
+            EventType::ImmediateRespawn,
+            flag(game_rules.immediate_respawn),
+        ),
+        game_event(
+            EventType::LimitedCrafting,
+            flag(game_rules.limited_crafting),
+        ),
+    ]
+}
+
 #[tracing::instrument(level = "trace", skip_all, err)]
-pub async fn signal_spawn_position(conn: &mut GameConnection) -> network::Result<()> {
+pub async fn signal_spawn_position(conn: &mut GameWriter) -> network::Result<()> {
     trace!("Signaling spawn position to client");
     let pos = config::get().spawn_location;
     conn.write(
@@ -170,8 +276,39 @@ pub async fn signal_spawn_position(conn: &mut GameConnection) -> network::Result
     Ok(())
 }
 
+#[tracing::instrument(level = "trace", skip_all, err)]
+pub async fn signal_world_border(
+    conn: &mut GameWriter,
+    border: &WorldBorder,
+) -> network::Result<()> {
+    trace!("Signaling world border to client");
+    let (old_size, lerp_time) = match &border.shrink {
+        Some(shrink) => (shrink.from, shrink.duration * 1000),
+        None => (border.diameter, 0),
+    };
+    conn.write(
+        ClientboundInitializeBorderPacket {
+            new_center_x: border.center_x,
+            new_center_z: border.center_z,
+            old_size,
+            new_size: border.diameter,
+            lerp_time,
+            new_absolute_max_size: MAX_BORDER_SIZE,
+            warning_blocks: border.warning_distance,
+            warning_time: border.warning_time,
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
 #[tracing::instrument(level = "trace", skip(conn), fields(%location), err)]
-pub async fn teleport_player(conn: &mut GameConnection, location: Location) -> network::Result<()> {
+pub async fn teleport_player(
+    conn: &mut GameWriter,
+    location: Location,
+    id: u32,
+) -> network::Result<()> {
     trace!("Teleporting player to location");
     conn.write(
         ClientboundPlayerPositionPacket {
@@ -187,7 +324,7 @@ pub async fn teleport_player(conn: &mut GameConnection, location: Location) -> n
                 y_rot: false,
                 x_rot: false,
             },
-            id: 1,
+            id,
         }
         .get(),
     )
@@ -195,72 +332,282 @@ pub async fn teleport_player(conn: &mut GameConnection, location: Location) -> n
     Ok(())
 }
 
-#[tracing::instrument(level = "trace", skip_all, err)]
-pub async fn signal_player_skin_layers(
-    conn: &mut GameConnection,
-    player: &PlayerRef,
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_system_message(conn: &mut GameWriter, message: &str) -> network::Result<()> {
+    trace!("Signaling system message to client");
+    conn.write(
+        ClientboundSystemChatPacket {
+            content: message.to_string().into(),
+            overlay: false,
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn, config), err)]
+pub async fn signal_title(
+    conn: &mut GameWriter,
+    config: &TitleConfig,
+    title: String,
+    subtitle: String,
 ) -> network::Result<()> {
-    trace!("Signaling player skin layers to client");
-    let player = player.lock().await;
-    if let Some(skin) = player.skin() {
+    trace!("Signaling title to client");
+    conn.write(
+        ClientboundSetTitlesAnimationPacket {
+            fade_in: config.fade_in,
+            stay: config.stay,
+            fade_out: config.fade_out,
+        }
+        .get(),
+    )
+    .await?;
+    conn.write(
+        ClientboundSetSubtitleTextPacket {
+            text: subtitle.into(),
+        }
+        .get(),
+    )
+    .await?;
+    // The title is sent last, as it makes the client show the title and subtitle
+    conn.write(ClientboundSetTitleTextPacket { text: title.into() }.get())
+        .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_action_bar(conn: &mut GameWriter, message: String) -> network::Result<()> {
+    trace!("Signaling action bar message to client");
+    conn.write(
+        ClientboundSetActionBarTextPacket {
+            text: message.into(),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn, config), err)]
+pub async fn signal_boss_bar_add(
+    conn: &mut GameWriter,
+    config: &BossBarConfig,
+    text: String,
+    progress: f32,
+) -> network::Result<()> {
+    trace!("Signaling boss bar to client");
+    let color = match config.color {
+        BossBarColor::Pink => clientbound_boss_event_packet::BossBarColor::Pink,
+        BossBarColor::Blue => clientbound_boss_event_packet::BossBarColor::Blue,
+        BossBarColor::Red => clientbound_boss_event_packet::BossBarColor::Red,
+        BossBarColor::Green => clientbound_boss_event_packet::BossBarColor::Green,
+        BossBarColor::Yellow => clientbound_boss_event_packet::BossBarColor::Yellow,
+        BossBarColor::Purple => clientbound_boss_event_packet::BossBarColor::Purple,
+        BossBarColor::White => clientbound_boss_event_packet::BossBarColor::White,
+    };
+    let overlay = match config.style {
+        BossBarStyle::Progress => clientbound_boss_event_packet::BossBarOverlay::Progress,
+        BossBarStyle::Notched6 => clientbound_boss_event_packet::BossBarOverlay::Notched6,
+        BossBarStyle::Notched10 => clientbound_boss_event_packet::BossBarOverlay::Notched10,
+        BossBarStyle::Notched12 => clientbound_boss_event_packet::BossBarOverlay::Notched12,
+        BossBarStyle::Notched20 => clientbound_boss_event_packet::BossBarOverlay::Notched20,
+    };
+    conn.write(
+        ClientboundBossEventPacket {
+            id: BOSS_BAR_ID,
+            operation: Operation::Add(AddOperation {
+                name: text.into(),
+                progress,
+                style: Style { color, overlay },
+                properties: Properties {
+                    darken_screen: false,
+                    play_music: false,
+                    create_world_fog: false,
+                },
+            }),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_boss_bar_update(
+    conn: &mut GameWriter,
+    text: String,
+    progress: f32,
+) -> network::Result<()> {
+    trace!("Signaling boss bar update to client");
+    conn.write(
+        ClientboundBossEventPacket {
+            id: BOSS_BAR_ID,
+            operation: Operation::UpdateName(text.into()),
+        }
+        .get(),
+    )
+    .await?;
+    conn.write(
+        ClientboundBossEventPacket {
+            id: BOSS_BAR_ID,
+            operation: Operation::UpdateProgress(progress),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_tab_list(
+    conn: &mut GameWriter,
+    header: String,
+    footer: String,
+) -> network::Result<()> {
+    trace!("Signaling tab list header and footer to client");
+    conn.write(
+        ClientboundTabListPacket {
+            header: header.into(),
+            footer: footer.into(),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Creates the sidebar objective and displays it, or changes its title if it already exists.
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_sidebar(
+    conn: &mut GameWriter,
+    title: String,
+    create: bool,
+) -> network::Result<()> {
+    trace!("Signaling sidebar to client");
+    let display = DisplayInfo {
+        display_name: title.into(),
+        render_type: RenderType::Integer,
+        // The scores only order the lines, so they are hidden
+        number_format: Some(NumberFormat::Blank),
+    };
+    conn.write(
+        ClientboundSetObjectivePacket {
+            objective_name: SIDEBAR_OBJECTIVE.to_string(),
+            method: if create {
+                Method::Add(display)
+            } else {
+                Method::Change(display)
+            },
+        }
+        .get(),
+    )
+    .await?;
+    if create {
         conn.write(
-            ClientboundSetEntityDataPacket {
-                id: player.entity_id(),
-                packed_items: EntityMetadataItems::new(vec![EntityDataItem {
-                    index: 17, // https://wiki.vg/Entity_metadata#Player
-                    value: EntityDataValue::Byte(skin.layers.to_bits()),
-                }]),
+            ClientboundSetDisplayObjectivePacket {
+                slot: DisplaySlot::Sidebar,
+                objective_name: SIDEBAR_OBJECTIVE.to_string(),
             }
             .get(),
         )
         .await?;
-    } else {
-        trace!("Player has no skin to send");
     }
     Ok(())
 }
 
-#[tracing::instrument(level = "trace", skip_all, err)]
-pub async fn signal_center_chunk(conn: &mut GameConnection) -> network::Result<()> {
-    trace!("Signaling center chunk to client");
-    conn.write(ClientboundSetChunkCacheCenterPacket { x: 0, z: 0 }.get())
-        .await?;
+/// Sets the text of a line of the sidebar.
+/// Each line keeps its score and only its displayed text changes, so the sidebar does not flicker.
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_sidebar_line(
+    conn: &mut GameWriter,
+    line: usize,
+    line_count: usize,
+    text: String,
+) -> network::Result<()> {
+    trace!("Signaling sidebar line to client");
+    conn.write(
+        ClientboundSetScorePacket {
+            owner: format!("line{line}"),
+            objective_name: SIDEBAR_OBJECTIVE.to_string(),
+            // Lines are sorted by descending score
+            score: (line_count - line) as u32,
+            display: Some(text.into()),
+            number_format: None,
+        }
+        .get(),
+    )
+    .await?;
     Ok(())
 }
 
-#[tracing::instrument(level = "trace", skip_all, fields(x = pos.x, z = pos.z), err)]
-pub async fn signal_chunk_update(
-    conn: &mut GameConnection,
-    pos: ChunkPos,
-    chunk: Chunk,
+#[tracing::instrument(level = "trace", skip(conn), fields(%location), err)]
+pub async fn signal_sound(
+    conn: &mut GameWriter,
+    sound: &str,
+    location: Location,
 ) -> network::Result<()> {
-    trace!("Signaling chunk update to client");
-    let mut chunk_data = Vec::new();
-    chunk.write_into(&mut chunk_data)?;
+    trace!("Signaling sound to client");
+    conn.write(
+        ClientboundSoundPacket {
+            sound: CustomRegistry::Custom(CustomSound {
+                location: ResourceLocation::new(sound),
+                fixed_range: None,
+            }),
+            source: SoundSource::Master,
+            // Sound positions are sent as fixed-point numbers with 3 fractional bits
+            x: (location.x() * 8.0) as i32,
+            y: (location.y() * 8.0) as i32,
+            z: (location.z() * 8.0) as i32,
+            volume: 1.0,
+            pitch: 1.0,
+            seed: 0,
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_keep_alive(conn: &mut GameWriter, id: u64) -> network::Result<()> {
+    trace!("Signaling keep alive to client");
+    conn.write(ClientboundKeepAlivePacket { id }.get()).await?;
+    Ok(())
+}
 
-    let mut heightmaps = NbtCompound::new();
-    for (kind, heightmap) in chunk.heightmaps {
-        let data = heightmap.data.data.iter().map(|x| *x as i64).collect();
-        heightmaps.insert(format!("{kind}"), NbtTag::LongArray(data));
+#[tracing::instrument(level = "trace", skip_all, err)]
+pub async fn signal_player_skin_layers(
+    conn: &mut GameWriter,
+    player: &PlayerRef,
+) -> network::Result<()> {
+    trace!("Signaling player skin layers to client");
+    let (entity_id, skin) = {
+        let player = player.lock().await;
+        (player.entity_id(), player.skin().cloned())
+    };
+    if let Some(skin) = skin {
+        signal_entity_skin_layers(conn, entity_id, skin.layers.to_bits()).await?;
+    } else {
+        trace!("Player has no skin to send");
     }
+    Ok(())
+}
 
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_entity_skin_layers(
+    conn: &mut GameWriter,
+    entity_id: u32,
+    layers: u8,
+) -> network::Result<()> {
+    trace!("Signaling skin layers of entity to client");
     conn.write(
-        ClientboundLevelChunkWithLightPacket {
-            x: pos.x,
-            z: pos.z,
-            chunk_data: ClientboundLevelChunkPacketData {
-                heightmaps: Nbt::Some(heightmaps.to_nbt()),
-                data: chunk_data,
-                block_entities: Vec::new(),
-            },
-            light_data: ClientboundLightUpdatePacketData {
-                sky_y_mask: Default::default(),         // todo
-                block_y_mask: Default::default(),       // todo
-                empty_sky_y_mask: Default::default(),   // todo
-                empty_block_y_mask: Default::default(), // todo
-                sky_updates: Default::default(),        // todo
-                block_updates: Default::default(),      // todo
-            },
+        ClientboundSetEntityDataPacket {
+            id: entity_id,
+            packed_items: EntityMetadataItems::new(vec![EntityDataItem {
+                index: 17, // https://wiki.vg/Entity_metadata#Player
+                value: EntityDataValue::Byte(layers),
+            }]),
         }
         .get(),
     )
@@ -268,19 +615,193 @@ pub async fn signal_chunk_update(
     Ok(())
 }
 
+#[tracing::instrument(level = "trace", skip(conn), fields(%location), err)]
+pub async fn signal_player_entity_add(
+    conn: &mut GameWriter,
+    entity_id: u32,
+    uuid: uuid::Uuid,
+    location: Location,
+) -> network::Result<()> {
+    trace!("Signaling player entity to client");
+    conn.write(
+        ClientboundAddEntityPacket {
+            id: entity_id,
+            uuid,
+            entity_type: EntityKind::Player,
+            position: location.to_vec3(),
+            x_rot: to_angle(location.pitch()),
+            y_rot: to_angle(location.yaw()),
+            y_head_rot: to_angle(location.yaw()),
+            data: 0,
+            x_vel: 0,
+            y_vel: 0,
+            z_vel: 0,
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), fields(%location), err)]
+pub async fn signal_entity_move(
+    conn: &mut GameWriter,
+    entity_id: u32,
+    location: Location,
+) -> network::Result<()> {
+    trace!("Signaling entity movement to client");
+    conn.write(
+        ClientboundTeleportEntityPacket {
+            id: entity_id,
+            position: location.to_vec3(),
+            y_rot: to_angle(location.yaw()),
+            x_rot: to_angle(location.pitch()),
+            on_ground: false,
+        }
+        .get(),
+    )
+    .await?;
+    // The head is turned separately from the body
+    conn.write(
+        ClientboundRotateHeadPacket {
+            entity_id,
+            y_head_rot: to_angle(location.yaw()),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_entities_remove(
+    conn: &mut GameWriter,
+    entity_ids: Vec<u32>,
+) -> network::Result<()> {
+    trace!("Signaling client to remove entities");
+    conn.write(ClientboundRemoveEntitiesPacket { entity_ids }.get())
+        .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_player_info_remove(
+    conn: &mut GameWriter,
+    profile_ids: Vec<uuid::Uuid>,
+) -> network::Result<()> {
+    trace!("Signaling client to remove players from the tab list");
+    conn.write(ClientboundPlayerInfoRemovePacket { profile_ids }.get())
+        .await?;
+    Ok(())
+}
+
+/// Converts an angle in degrees to the steps of 1/256 of a full turn it is sent as.
+fn to_angle(degrees: f32) -> i8 {
+    (degrees / 360.0 * 256.0) as i32 as i8
+}
+
+#[tracing::instrument(level = "trace", skip_all, fields(x = pos.x, z = pos.z), err)]
+pub async fn signal_center_chunk(conn: &mut GameWriter, pos: ChunkPos) -> network::Result<()> {
+    trace!("Signaling center chunk to client");
+    conn.write(ClientboundSetChunkCacheCenterPacket { x: pos.x, z: pos.z }.get())
+        .await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip_all, fields(x = pos.x, z = pos.z), err)]
+pub async fn signal_chunk_update(conn: &mut GameWriter, pos: ChunkPos) -> network::Result<()> {
+    trace!("Signaling chunk update to client");
+    conn.write_encoded(&cache::chunk(pos)).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip_all, fields(x = pos.x, z = pos.z), err)]
+pub async fn signal_chunk_forget(conn: &mut GameWriter, pos: ChunkPos) -> network::Result<()> {
+    trace!("Signaling client to forget chunk");
+    conn.write(ClientboundForgetLevelChunkPacket { pos }.get())
+        .await?;
+    Ok(())
+}
+
 #[tracing::instrument(level = "trace", skip_all, fields(len = chunks.len()), err)]
 pub async fn signal_chunk_batch_update(
-    conn: &mut GameConnection,
-    chunks: Vec<(ChunkPos, Chunk)>,
+    conn: &mut GameWriter,
+    chunks: Vec<ChunkPos>,
 ) -> network::Result<()> {
     trace!("Signaling chunk batch update to client");
     let batch_size = chunks.len() as u32;
     conn.write(ClientboundChunkBatchStartPacket {}.get())
         .await?;
-    for (pos, chunk) in chunks {
-        signal_chunk_update(conn, pos, chunk).await?;
+    for pos in chunks {
+        signal_chunk_update(conn, pos).await?;
     }
     conn.write(ClientboundChunkBatchFinishedPacket { batch_size }.get())
         .await?;
     Ok(())
 }
+
+#[tracing::instrument(level = "trace", skip_all, fields(len = nodes.len()), err)]
+pub async fn signal_commands(
+    conn: &mut GameWriter,
+    nodes: Vec<BrigadierNodeStub>,
+) -> network::Result<()> {
+    trace!("Signaling commands to client");
+    conn.write(
+        ClientboundCommandsPacket {
+            entries: nodes,
+            root_index: 0,
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Asks the proxy to send the player to another server, using the BungeeCord `Connect` message.
+#[tracing::instrument(level = "trace", skip(conn), err)]
+pub async fn signal_proxy_connect(conn: &mut GameWriter, server: &str) -> network::Result<()> {
+    trace!("Signaling proxy to connect player to server");
+    let mut data = Vec::new();
+    for s in ["Connect", server] {
+        // Strings are read with Java's `DataInput::readUTF`, prefixed with their length as a u16
+        (s.len() as u16).write_into(&mut data)?;
+        data.extend_from_slice(s.as_bytes());
+    }
+    conn.write(
+        ClientboundCustomPayloadPacket {
+            identifier: ResourceLocation::new(BUNGEECORD_CHANNEL),
+            data: data.into(),
+        }
+        .get(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Connects a game writer to a client, returning the writer and the packets the client reads.
+#[cfg(test)]
+pub async fn test_connection() -> (
+    GameWriter,
+    tokio::sync::mpsc::UnboundedReceiver<ClientboundGamePacket>,
+) {
+    use azalea_protocol::{connect::Connection, packets::game::ServerboundGamePacket};
+    use tokio::net::{TcpListener, TcpStream};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (_, writer) =
+        network::GameConnection::wrap(listener.accept().await.unwrap().0).into_split();
+    let mut client = Connection::<ClientboundGamePacket, ServerboundGamePacket>::wrap(stream);
+    let (sender, packets) = tokio::sync::mpsc::unbounded_channel();
+    // Read concurrently, so that large batches cannot fill up the socket
+    tokio::spawn(async move {
+        while let Ok(packet) = client.read().await {
+            if sender.send(packet).is_err() {
+                break;
+            }
+        }
+    });
+    (writer, packets)
+}
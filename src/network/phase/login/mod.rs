@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use azalea_protocol::packets::login::ServerboundLoginPacket;
 use rand::Rng;
 use tracing::*;
@@ -54,7 +56,7 @@ pub async fn try_handle(
 
                 utils::validate_player_name(&mut conn, &packet.name).await?;
                 let uuid = azalea_auth::offline::generate_uuid(&packet.name);
-                let player = Player::new(addr, packet.name, uuid, None);
+                let player = Player::new(addr, packet.name, uuid, None, HashMap::new());
                 player_lock = Some(utils::signal_login_success(&mut conn, server, player).await?);
                 state = State::PhaseSwitch; // wait for login ack before transitioning
             }
@@ -83,7 +85,13 @@ pub async fn try_handle(
                     )
                     .await?;
 
-                    let player = Player::new(info.addr.into(), info.name, info.uuid, info.skin);
+                    let player = Player::new(
+                        info.addr.into(),
+                        info.name,
+                        info.uuid,
+                        info.skin,
+                        info.properties,
+                    );
                     player_lock =
                         Some(utils::signal_login_success(&mut conn, server, player).await?);
                     state = State::PhaseSwitch; // wait for login ack before transitioning
@@ -0,0 +1,262 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use tracing::*;
+
+use crate::{config::ty::QueueConfig, player::Player};
+
+/// Number of recent releases the release rate is estimated from.
+const RATE_WINDOW: usize = 20;
+
+/// A player waiting in the queue.
+struct Entry {
+    uuid: uuid::Uuid,
+    priority: i32,
+}
+
+#[derive(Default)]
+struct State {
+    /// Players waiting, ordered by priority (highest first) and then by arrival
+    waiting: VecDeque<Entry>,
+    /// Players that were released but have not been sent off yet
+    released: HashSet<uuid::Uuid>,
+    /// When the most recent releases happened and how many players each released, oldest first
+    recent_releases: VecDeque<(Instant, usize)>,
+}
+
+/// The players waiting to be sent onward, when the limbo is used as a waiting room.
+pub struct Queue {
+    state: Mutex<State>,
+    /// Priorities from the priority file, by lowercase name or UUID
+    priorities: HashMap<String, i32>,
+}
+
+impl Queue {
+    pub fn new(config: &QueueConfig) -> anyhow::Result<Self> {
+        let priorities = match &config.priority_file {
+            Some(path) => read_priorities(path)
+                .context(format!("Failed to read queue priority file {path}"))?,
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            state: Default::default(),
+            priorities,
+        })
+    }
+
+    /// Returns the priority of a player, from the priority file or else from its forwarded property.
+    pub fn priority_of(&self, config: &QueueConfig, player: &Player) -> i32 {
+        let from_file = [player.name().to_lowercase(), player.uuid().to_string()]
+            .iter()
+            .find_map(|key| self.priorities.get(key).copied());
+        let from_property = || {
+            let property = config.priority_property.as_ref()?;
+            player.property(property)?.trim().parse().ok()
+        };
+        from_file.or_else(from_property).unwrap_or_default()
+    }
+
+    /// Adds a player behind the players with the same or a higher priority.
+    /// A player that reconnects while still waiting keeps its position.
+    pub fn join(&self, uuid: uuid::Uuid, priority: i32) {
+        let mut state = self.state.lock().unwrap();
+        if state.waiting.iter().any(|entry| entry.uuid == uuid) {
+            return;
+        }
+        let index = state
+            .waiting
+            .iter()
+            .position(|entry| entry.priority < priority)
+            .unwrap_or(state.waiting.len());
+        state.waiting.insert(index, Entry { uuid, priority });
+    }
+
+    /// Removes a player that left the limbo.
+    pub fn leave(&self, uuid: uuid::Uuid) {
+        let mut state = self.state.lock().unwrap();
+        state.waiting.retain(|entry| entry.uuid != uuid);
+        state.released.remove(&uuid);
+    }
+
+    /// Returns the position of a player (starting at 1) and the number of waiting players,
+    /// if it is waiting.
+    pub fn position(&self, uuid: uuid::Uuid) -> Option<(usize, usize)> {
+        let state = self.state.lock().unwrap();
+        let index = state.waiting.iter().position(|entry| entry.uuid == uuid)?;
+        Some((index + 1, state.waiting.len()))
+    }
+
+    /// Returns the number of waiting players.
+    pub fn size(&self) -> usize {
+        self.state.lock().unwrap().waiting.len()
+    }
+
+    /// Estimates the time until the player at the given position is released,
+    /// from the rate at which players were released since the oldest of the recent releases.
+    /// The estimate grows while no players are released, and is unknown until two releases happened.
+    pub fn estimated_wait(&self, position: usize) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        let (oldest, _) = state.recent_releases.front()?;
+        let released: usize = state
+            .recent_releases
+            .iter()
+            .skip(1)
+            .map(|(_, amount)| amount)
+            .sum();
+        if released == 0 {
+            return None;
+        }
+        let per_player = oldest.elapsed() / released as u32;
+        Some(per_player * position as u32)
+    }
+
+    /// Releases the players at the front of the queue, returning how many were released.
+    pub fn release(&self, amount: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let amount = amount.min(state.waiting.len());
+        let released: Vec<Entry> = state.waiting.drain(..amount).collect();
+        state
+            .released
+            .extend(released.iter().map(|entry| entry.uuid));
+        if amount > 0 {
+            state.recent_releases.push_back((Instant::now(), amount));
+            if state.recent_releases.len() > RATE_WINDOW {
+                state.recent_releases.pop_front();
+            }
+        }
+        amount
+    }
+
+    /// Returns whether a player was released, forgetting it if so.
+    pub fn take_released(&self, uuid: uuid::Uuid) -> bool {
+        self.state.lock().unwrap().released.remove(&uuid)
+    }
+
+    /// Releases players at a fixed rate, if configured.
+    pub async fn run_releases(&self, config: &QueueConfig) {
+        if config.release_amount == 0 {
+            return;
+        }
+        let period = Duration::from_secs(config.release_interval.max(1));
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let released = self.release(config.release_amount);
+            if released > 0 {
+                debug!(released, "Released players from the queue");
+            }
+        }
+    }
+}
+
+/// Reads a priority file, with one `<name or UUID> <priority>` per line.
+/// Empty lines and lines starting with `#` are ignored.
+fn read_priorities(path: &str) -> anyhow::Result<HashMap<String, i32>> {
+    let mut priorities = HashMap::new();
+    for (i, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, priority)) = line.split_once(char::is_whitespace) else {
+            anyhow::bail!("Line {} is missing a priority", i + 1);
+        };
+        let priority = priority
+            .trim()
+            .parse()
+            .context(format!("Line {} has an invalid priority", i + 1))?;
+        priorities.insert(key.to_lowercase(), priority);
+    }
+    Ok(priorities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue() -> Queue {
+        Queue {
+            state: Default::default(),
+            priorities: HashMap::new(),
+        }
+    }
+
+    fn uuid(id: u128) -> uuid::Uuid {
+        uuid::Uuid::from_u128(id)
+    }
+
+    fn order(queue: &Queue) -> Vec<u128> {
+        let state = queue.state.lock().unwrap();
+        state
+            .waiting
+            .iter()
+            .map(|entry| entry.uuid.as_u128())
+            .collect()
+    }
+
+    #[test]
+    fn orders_by_priority_then_arrival() {
+        let queue = queue();
+        for (id, priority) in [(1, 0), (2, 0), (3, 5), (4, 1), (5, 5), (6, -1)] {
+            queue.join(uuid(id), priority);
+        }
+        assert_eq!(order(&queue), [3, 5, 4, 1, 2, 6]);
+        assert_eq!(queue.position(uuid(4)), Some((3, 6)));
+        assert_eq!(queue.position(uuid(7)), None);
+
+        assert_eq!(queue.release(2), 2);
+        assert_eq!(order(&queue), [4, 1, 2, 6]);
+        assert!(queue.take_released(uuid(3)));
+        assert!(!queue.take_released(uuid(3)));
+        assert!(!queue.take_released(uuid(4)));
+        assert_eq!(queue.release(10), 4);
+        assert_eq!(queue.size(), 0);
+    }
+
+    #[test]
+    fn rejoining_keeps_the_position() {
+        let queue = queue();
+        queue.join(uuid(1), 0);
+        queue.join(uuid(2), 0);
+        // Reconnecting while waiting, even with a higher priority, does not add a second entry
+        queue.join(uuid(1), 10);
+        assert_eq!(order(&queue), [1, 2]);
+
+        // Once left, the player joins at the back again
+        queue.leave(uuid(1));
+        queue.join(uuid(1), 0);
+        assert_eq!(order(&queue), [2, 1]);
+    }
+
+    #[test]
+    fn estimates_wait_from_recent_releases() {
+        let queue = queue();
+        for id in 0..30 {
+            queue.join(uuid(id), 0);
+        }
+        assert_eq!(queue.estimated_wait(1), None);
+        queue.release(1);
+        // A single release does not tell the rate yet
+        assert_eq!(queue.estimated_wait(1), None);
+
+        for _ in 0..24 {
+            queue.release(1);
+        }
+        {
+            let mut state = queue.state.lock().unwrap();
+            assert_eq!(state.recent_releases.len(), RATE_WINDOW);
+            // One player released every second, the oldest release in the window 19 seconds ago
+            let now = Instant::now();
+            for (i, (at, _)) in state.recent_releases.iter_mut().enumerate() {
+                *at = now - Duration::from_secs((RATE_WINDOW - 1 - i) as u64);
+            }
+        }
+        let wait = queue.estimated_wait(10).unwrap();
+        assert!(wait >= Duration::from_secs(10) && wait < Duration::from_secs(11));
+    }
+}
@@ -1,16 +1,15 @@
 use std::sync::{atomic::AtomicU32, Arc};
 
-use crate::{config, player::Player};
+use crate::{
+    config::{self, ty::Ambience},
+    player::Player,
+};
 use anyhow::Context;
+use azalea_protocol::packets::game::ClientboundGamePacket;
 use dashmap::DashMap;
 use tokio::{net::TcpListener, sync::Mutex};
 use tracing::*;
 
-pub mod constants {
-    pub const VIEW_DISTANCE: u32 = 8;
-    pub const SIMULATION_DISTANCE: u32 = 8;
-}
-
 pub type AServer = Arc<Server>;
 pub type PlayerRef = Arc<Mutex<Player>>;
 
@@ -18,12 +17,31 @@ pub struct Server {
     listener: TcpListener,
     players: DashMap<uuid::Uuid, Arc<Mutex<Player>>>,
     entity_id_counter: AtomicU32,
+    /// The players waiting to be sent onward, if the limbo is used as a waiting room
+    queue: Option<super::queue::Queue>,
+    /// The backend servers that are watched, if players are sent back to them
+    backends: Option<super::backends::Backends>,
+    /// The time of day, weather and game rules, which can be reloaded
+    ambience: std::sync::RwLock<Ambience>,
 }
 
 impl Server {
     pub async fn bind(host: &str, port: u16) -> anyhow::Result<AServer> {
         // Read and initialize registry data
         crate::utils::registry_data::init()?;
+        // Load the world (requires registry data for biomes)
+        crate::world::init()?;
+        // Encode the packets shared by all players (requires the world)
+        super::cache::init()?;
+        // Read the queue priorities, if the limbo is used as a waiting room
+        let queue = match &config::get().queue {
+            Some(config) => Some(super::queue::Queue::new(config)?),
+            None => None,
+        };
+        let backends = config::get()
+            .auto_return
+            .as_ref()
+            .map(|_| super::backends::Backends::default());
         // Bind to the specified host and port
         let listener = TcpListener::bind((host, port))
             .await
@@ -33,10 +51,39 @@ impl Server {
             listener,
             players: Default::default(),
             entity_id_counter: Default::default(),
+            queue,
+            backends,
+            ambience: std::sync::RwLock::new(config::get().world.ambience()),
         }))
     }
 
+    /// Binds a server to a free local port, without loading the world or the packet cache.
+    #[cfg(test)]
+    pub async fn bind_test() -> AServer {
+        Arc::new(Self {
+            listener: TcpListener::bind("127.0.0.1:0").await.unwrap(),
+            players: Default::default(),
+            entity_id_counter: Default::default(),
+            queue: None,
+            backends: None,
+            ambience: std::sync::RwLock::new(config::get().world.ambience()),
+        })
+    }
+
     pub async fn listen(self: &AServer) -> anyhow::Result<()> {
+        // Handle commands typed in the console and release queued players over time
+        tokio::spawn(Arc::clone(self).handle_console());
+        if let Some(config) = &config::get().queue {
+            let this = Arc::clone(self);
+            tokio::spawn(async move { this.queue().unwrap().run_releases(config).await });
+        }
+        // Watch the backend servers, if players are sent back to them
+        if let Some(config) = &config::get().auto_return {
+            for backend in &config.backends {
+                let this = Arc::clone(self);
+                tokio::spawn(async move { this.backends().unwrap().poll(config, backend).await });
+            }
+        }
         debug!("Listening for incoming connections");
         loop {
             // Wait for incoming connections...
@@ -69,9 +116,45 @@ impl Server {
         }
     }
 
-    // TODO: Remove player on disconnect
-    pub fn remove_player(self: &AServer, uuid: uuid::Uuid) {
-        self.players.remove(&uuid);
+    /// Removes a player, unless it was already replaced by a newer connection with the same UUID.
+    pub fn remove_player(self: &AServer, uuid: uuid::Uuid, player: &PlayerRef) {
+        if self
+            .players
+            .remove_if(&uuid, |_, stored| Arc::ptr_eq(stored, player))
+            .is_none()
+        {
+            return;
+        }
+        if let Some(queue) = &self.queue {
+            queue.leave(uuid);
+        }
+    }
+
+    /// Returns whether a player was replaced by a newer connection with the same UUID.
+    pub fn is_replaced(self: &AServer, uuid: &uuid::Uuid, player: &PlayerRef) -> bool {
+        self.players
+            .get(uuid)
+            .is_some_and(|stored| !Arc::ptr_eq(&stored, player))
+    }
+
+    /// The players waiting to be sent onward, if the limbo is used as a waiting room.
+    pub fn queue(self: &AServer) -> Option<&super::queue::Queue> {
+        self.queue.as_ref()
+    }
+
+    /// The backend servers that are watched, if players are sent back to them.
+    pub fn backends(self: &AServer) -> Option<&super::backends::Backends> {
+        self.backends.as_ref()
+    }
+
+    /// The current time of day, weather and game rules.
+    pub fn ambience(self: &AServer) -> Ambience {
+        self.ambience.read().unwrap().clone()
+    }
+
+    /// Replaces the time of day, weather and game rules, without sending them to the players.
+    pub fn set_ambience(self: &AServer, ambience: Ambience) {
+        *self.ambience.write().unwrap() = ambience;
     }
 
     /// Current player count, returned as an i32 (for protocol compatibility)
@@ -79,6 +162,19 @@ impl Server {
         self.players.len() as i32
     }
 
+    /// Returns every player, in any phase.
+    /// The players are collected, so no map shard stays locked while waiting for a player.
+    pub fn players(self: &AServer) -> Vec<PlayerRef> {
+        self.players.iter().map(|p| Arc::clone(&p)).collect()
+    }
+
+    /// Sends a packet to every player in the game phase.
+    pub async fn broadcast(self: &AServer, packet: ClientboundGamePacket) {
+        for player in self.players() {
+            player.lock().await.send(packet.clone());
+        }
+    }
+
     pub fn is_full(self: &AServer) -> bool {
         let max_players = config::get().max_players;
         max_players > 0 && self.get_player_count() >= max_players
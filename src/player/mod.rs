@@ -1,9 +1,22 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use azalea_auth::game_profile::GameProfile;
+use azalea_protocol::packets::game::ClientboundGamePacket;
+use tokio::sync::mpsc;
 
-use crate::network::server::AServer;
+use crate::{
+    config::{self, ty::Location},
+    network::server::AServer,
+};
 
 pub mod skin;
 
+/// The lowest view distance a client can set.
+const MIN_VIEW_DISTANCE: u32 = 2;
+
 pub struct Player {
     // Player Data
     /// The IP address of the player.
@@ -14,9 +27,23 @@ pub struct Player {
     uuid: uuid::Uuid,
     /// The skin of the player.
     skin: Option<skin::PlayerSkin>,
+    /// The other game profile properties of the player (forwarded by the proxy), by name.
+    properties: HashMap<String, String>,
+    /// The view distance set in the player's client, if it has been received.
+    client_view_distance: Option<u32>,
+    /// The latency of the player, once it has been measured.
+    latency: Option<Duration>,
+    /// When the player logged in.
+    logged_in_at: Instant,
+    /// When the player last moved, looked around, chatted or interacted.
+    last_active: Instant,
     // Entity Data
     /// The entity ID of the player.
     entity_id: u32,
+    /// The last known location of the player.
+    location: Location,
+    /// Sends packets to the player's connection, once it has entered the game phase.
+    packets: Option<mpsc::UnboundedSender<ClientboundGamePacket>>,
 }
 
 impl Player {
@@ -26,20 +53,46 @@ impl Player {
         name: String,
         uuid: uuid::Uuid,
         skin: Option<skin::PlayerSkin>,
+        properties: HashMap<String, String>,
     ) -> Self {
         Self {
             addr,
             name,
             uuid,
             skin,
+            properties,
+            client_view_distance: None,
+            latency: None,
+            logged_in_at: Instant::now(),
+            last_active: Instant::now(),
             entity_id: 0,
+            location: config::get().spawn_location,
+            packets: None,
         }
     }
 
     /// Initializes the player.
     /// Must be called once the player enters the game phase.
-    pub fn init(&mut self, server: &AServer) {
+    pub fn init(
+        &mut self,
+        server: &AServer,
+        packets: mpsc::UnboundedSender<ClientboundGamePacket>,
+    ) {
         self.entity_id = server.next_entity_id();
+        self.packets = Some(packets);
+    }
+
+    /// Returns whether the player has entered the game phase.
+    pub fn in_game(&self) -> bool {
+        self.packets.is_some()
+    }
+
+    /// Sends a packet to the player, if it is in the game phase.
+    pub fn send(&self, packet: ClientboundGamePacket) {
+        if let Some(packets) = &self.packets {
+            // The connection may already be closed, in which case the packet is dropped
+            let _ = packets.send(packet);
+        }
     }
 
     /// Returns the IP address of the player.
@@ -62,11 +115,63 @@ impl Player {
         self.skin.as_ref()
     }
 
+    /// Returns the value of a game profile property of the player (other than its skin).
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+
+    /// Returns the view distance of the player (in chunks), limited by the server's view distance.
+    pub fn view_distance(&self) -> u32 {
+        let max = config::get().view_distance;
+        self.client_view_distance
+            .map_or(max, |distance| distance.max(MIN_VIEW_DISTANCE).min(max))
+    }
+
+    /// Sets the view distance set in the player's client.
+    pub fn set_client_view_distance(&mut self, distance: u32) {
+        self.client_view_distance = Some(distance);
+    }
+
+    /// Returns the latency of the player, if it has been measured.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Sets the latency of the player, as measured by a keep alive round trip.
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = Some(latency);
+    }
+
+    /// Returns how long ago the player logged in.
+    pub fn session_duration(&self) -> Duration {
+        self.logged_in_at.elapsed()
+    }
+
+    /// Returns how long the player has not been active.
+    pub fn idle_time(&self) -> Duration {
+        self.last_active.elapsed()
+    }
+
+    /// Marks the player as active (e.g. moving, chatting or interacting).
+    pub fn mark_active(&mut self) {
+        self.last_active = Instant::now();
+    }
+
     /// Returns the entity ID of the player.
     pub fn entity_id(&self) -> u32 {
         self.entity_id
     }
 
+    /// Returns the last known location of the player.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Sets the last known location of the player.
+    pub fn set_location(&mut self, location: Location) {
+        self.location = location;
+    }
+
     /// Returns a clone of the player's game profile (including skin data).
     pub fn game_profile(&self) -> GameProfile {
         let mut profile = azalea_auth::game_profile::GameProfile::new(self.uuid, self.name.clone());
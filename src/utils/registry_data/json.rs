@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use anyhow::Context;
+use azalea_core::resource_location::ResourceLocation;
+use serde_json::{Map, Number, Value};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
+
+/// Reads the entries of a registry from a datapack-style directory
+/// (`<root>/data/<namespace>/<registry>/<name>.json`), returning their names and elements.
+pub fn read_entries(
+    root: &Path,
+    registry: &ResourceLocation,
+) -> anyhow::Result<Vec<(String, NbtCompound)>> {
+    let mut entries = Vec::new();
+    let data = root.join("data");
+    let namespaces = std::fs::read_dir(&data)
+        .context(format!("Failed to read datapack folder {}", data.display()))?;
+    for namespace in namespaces {
+        let namespace = namespace?;
+        let dir = namespace.path().join(&registry.path);
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut files: Vec<_> = std::fs::read_dir(&dir)?
+            .map(|file| file.map(|file| file.path()))
+            .collect::<std::io::Result<_>>()?;
+        files.sort();
+        for file in files {
+            if file.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let (Some(namespace), Some(name)) = (
+                namespace.file_name().to_str(),
+                file.file_stem().and_then(|s| s.to_str()),
+            ) else {
+                continue;
+            };
+            let element = read_element(&file)
+                .context(format!("Invalid registry entry {}", file.display()))?;
+            entries.push((format!("{namespace}:{name}"), element));
+        }
+    }
+    Ok(entries)
+}
+
+fn read_element(file: &Path) -> anyhow::Result<NbtCompound> {
+    match serde_json::from_slice(&std::fs::read(file)?)? {
+        Value::Object(map) => to_compound(&map),
+        _ => anyhow::bail!("Registry entries must be JSON objects"),
+    }
+}
+
+/// Converts a JSON object to an NBT compound, using the tag types vanilla's codecs accept.
+fn to_compound(map: &Map<String, Value>) -> anyhow::Result<NbtCompound> {
+    let mut compound = NbtCompound::new();
+    for (key, value) in map {
+        compound.insert(
+            key.as_str(),
+            to_tag(value).context(format!("In key {key}"))?,
+        );
+    }
+    Ok(compound)
+}
+
+fn to_tag(value: &Value) -> anyhow::Result<NbtTag> {
+    Ok(match value {
+        Value::Null => anyhow::bail!("Null values are not supported"),
+        Value::Bool(b) => NbtTag::Byte(*b as i8),
+        Value::Number(n) => number_tag(n),
+        Value::String(s) => NbtTag::String(s.as_str().into()),
+        Value::Array(values) => NbtTag::List(to_list(values)?),
+        Value::Object(map) => NbtTag::Compound(to_compound(map)?),
+    })
+}
+
+fn number_tag(n: &Number) -> NbtTag {
+    match n.as_i64() {
+        Some(n) => i32::try_from(n).map_or(NbtTag::Long(n), NbtTag::Int),
+        None => NbtTag::Double(n.as_f64().unwrap_or_default()),
+    }
+}
+
+/// NBT lists hold a single tag type, so numbers are widened to the widest type in the list.
+fn to_list(values: &[Value]) -> anyhow::Result<NbtList> {
+    let Some(first) = values.first() else {
+        return Ok(NbtList::Empty);
+    };
+    if values
+        .iter()
+        .any(|v| std::mem::discriminant(v) != std::mem::discriminant(first))
+    {
+        anyhow::bail!("Lists must not mix value types");
+    }
+    Ok(match first {
+        Value::Null => anyhow::bail!("Null values are not supported"),
+        Value::Bool(_) => NbtList::Byte(
+            values
+                .iter()
+                .filter_map(Value::as_bool)
+                .map(|b| b as i8)
+                .collect(),
+        ),
+        Value::Number(_) => {
+            let ints: Option<Vec<i64>> = values.iter().map(Value::as_i64).collect();
+            match ints {
+                Some(ints) if ints.iter().all(|n| i32::try_from(*n).is_ok()) => {
+                    NbtList::Int(ints.into_iter().map(|n| n as i32).collect())
+                }
+                Some(longs) => NbtList::Long(longs),
+                None => NbtList::Double(values.iter().filter_map(Value::as_f64).collect()),
+            }
+        }
+        Value::String(_) => NbtList::String(
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(Into::into)
+                .collect(),
+        ),
+        Value::Array(_) => NbtList::List(
+            values
+                .iter()
+                .filter_map(Value::as_array)
+                .map(|values| to_list(values))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Value::Object(_) => NbtList::Compound(
+            values
+                .iter()
+                .filter_map(Value::as_object)
+                .map(to_compound)
+                .collect::<anyhow::Result<_>>()?,
+        ),
+    })
+}
@@ -1,36 +1,198 @@
-use std::io::Cursor;
+use std::{
+    collections::HashSet,
+    io::{Cursor, Read},
+    path::Path,
+};
 
 use anyhow::Context;
-use azalea_core::registry_holder::RegistryHolder;
+use azalea_core::{registry_holder::RegistryHolder, resource_location::ResourceLocation};
+use simdnbt::owned::{NbtCompound, NbtList, NbtTag};
 use tracing::*;
 
+use crate::config;
+
+mod json;
+
 // A very big thank you to Norbiros for dumping this packet:
 // https://gist.github.com/Norbiros/f604ce46821e68c50260a169a9921560
 // NOTE: This MUST be NBT and NOT Network NBT! Use net2nbt to convert.
 const REGISTRY_BYTES: &[u8] = include_bytes!("dimension_registry.nbt");
 static mut REGISTRY_DATA: Option<RegistryHolder> = None;
 
+/// Registries the client expects to receive.
+const REQUIRED_REGISTRIES: &[&str] = &[
+    "minecraft:chat_type",
+    "minecraft:damage_type",
+    "minecraft:dimension_type",
+    "minecraft:trim_material",
+    "minecraft:trim_pattern",
+    "minecraft:worldgen/biome",
+];
+
 pub fn init() -> anyhow::Result<()> {
     info!("Initializing registry data");
 
-    let nbt = simdnbt::borrow::Nbt::read(&mut Cursor::new(REGISTRY_BYTES))
-        .context("Failed to parse registry data")?;
-    let holder = RegistryHolder {
-        map: simdnbt::Deserialize::from_compound(&nbt.unwrap())?,
+    let holder = match &config::get().registry_data {
+        None => parse(REGISTRY_BYTES).context("Failed to parse built-in registry data")?,
+        Some(path) if Path::new(path).is_dir() => {
+            let mut holder =
+                parse(REGISTRY_BYTES).context("Failed to parse built-in registry data")?;
+            merge_datapack(&mut holder, Path::new(path))?;
+            holder
+        }
+        Some(path) => {
+            let data =
+                std::fs::read(path).context(format!("Failed to read registry data {path}"))?;
+            parse(&decompress(data)?).context(format!("Failed to parse registry data {path}"))?
+        }
     };
-    if holder.map.is_empty() {
-        anyhow::bail!("Corrupted registry data (empty)");
-    }
+    validate(&holder)?;
 
     unsafe { REGISTRY_DATA = Some(holder) };
     Ok(())
 }
 
-/// Returns a clone of the registry data.
-pub fn get() -> RegistryHolder {
+/// Parses registry data in the same (uncompressed NBT) format as the built-in data.
+fn parse(data: &[u8]) -> anyhow::Result<RegistryHolder> {
+    let nbt = simdnbt::borrow::Nbt::read(&mut Cursor::new(data))?;
+    let simdnbt::borrow::Nbt::Some(nbt) = nbt else {
+        anyhow::bail!("Registry data is empty");
+    };
+    Ok(RegistryHolder {
+        map: simdnbt::Deserialize::from_compound(&nbt)?,
+    })
+}
+
+/// NBT files are usually gzip-compressed, but may also be stored as-is.
+fn decompress(data: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    if !data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(data);
+    }
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Adds or replaces registry entries with the ones in a datapack-style directory of JSON files.
+/// Replaced entries keep their ID, new entries are appended.
+#[tracing::instrument(skip(holder))]
+fn merge_datapack(holder: &mut RegistryHolder, root: &Path) -> anyhow::Result<()> {
+    for (registry, compound) in holder.map.iter_mut() {
+        let entries = json::read_entries(root, registry)?;
+        if entries.is_empty() {
+            continue;
+        }
+        debug!(%registry, count = entries.len(), "Loading registry entries from datapack");
+
+        let mut values = compound
+            .list("value")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+            .to_vec();
+        for (name, element) in entries {
+            let existing = values
+                .iter()
+                .position(|entry| entry.string("name").is_some_and(|n| n.to_str() == name));
+            let id = match existing {
+                Some(index) => values[index].int("id").unwrap_or(index as i32),
+                None => values
+                    .iter()
+                    .filter_map(|entry| entry.int("id"))
+                    .max()
+                    .map_or(0, |id| id + 1),
+            };
+            let mut entry = NbtCompound::new();
+            entry.insert("name", NbtTag::String(name.into()));
+            entry.insert("id", NbtTag::Int(id));
+            entry.insert("element", NbtTag::Compound(element));
+            match existing {
+                Some(index) => values[index] = entry,
+                None => values.push(entry),
+            }
+        }
+
+        let mut merged = NbtCompound::new();
+        for (key, value) in compound.iter() {
+            if key.to_str() != "value" {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        merged.insert("value", NbtTag::List(NbtList::Compound(values)));
+        *compound = merged;
+    }
+    Ok(())
+}
+
+/// Checks that every required registry is present and that all of its entries are well-formed,
+/// reporting every problem that was found.
+fn validate(holder: &RegistryHolder) -> anyhow::Result<()> {
+    let mut problems = Vec::new();
+    for registry in REQUIRED_REGISTRIES {
+        let Some(compound) = holder.map.get(&ResourceLocation::new(registry)) else {
+            problems.push(format!("Registry {registry} is missing"));
+            continue;
+        };
+        let entries = compound
+            .list("value")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default();
+        if entries.is_empty() {
+            problems.push(format!("Registry {registry} has no entries"));
+        }
+        let mut ids = HashSet::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let name = entry
+                .string("name")
+                .map_or_else(|| format!("#{index}"), |name| name.to_str().into_owned());
+            match entry.int("id") {
+                Some(id) if !ids.insert(id) => {
+                    problems.push(format!("Entry {name} in {registry} has duplicate ID {id}"))
+                }
+                Some(_) => {}
+                None => problems.push(format!("Entry {name} in {registry} has no ID")),
+            }
+            if entry.compound("element").is_none() {
+                problems.push(format!("Entry {name} in {registry} has no element"));
+            }
+        }
+    }
+
+    for problem in &problems {
+        error!("Invalid registry data: {problem}");
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("Invalid registry data ({} problems found)", problems.len());
+    }
+    Ok(())
+}
+
+/// Returns the registry data.
+pub fn get() -> &'static RegistryHolder {
     unsafe {
         REGISTRY_DATA
-            .clone()
+            .as_ref()
             .expect("Registry data not initialized")
     }
 }
+
+/// Returns an entry (name, ID and element) in the given registry, if it exists.
+fn find_entry(registry: &str, name: &str) -> Option<&'static NbtCompound> {
+    let name = ResourceLocation::new(name).to_string();
+    get()
+        .map
+        .get(&ResourceLocation::new(registry))?
+        .list("value")?
+        .compounds()?
+        .iter()
+        .find(|entry| entry.string("name").is_some_and(|n| n.to_str() == name))
+}
+
+/// Returns the network ID of an entry in the given registry, if it exists.
+pub fn entry_id(registry: &str, name: &str) -> Option<u32> {
+    find_entry(registry, name)?.int("id").map(|id| id as u32)
+}
+
+/// Returns the element of an entry in the given registry, if it exists.
+pub fn entry_element(registry: &str, name: &str) -> Option<&'static NbtCompound> {
+    find_entry(registry, name)?.compound("element")
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::player::skin;
 use azalea_buf::{McBufReadable, McBufVarReadable};
 
@@ -18,6 +20,8 @@ pub struct ForwardingInfo {
     pub uuid: uuid::Uuid,
     // The skin of the player.
     pub skin: Option<skin::PlayerSkin>,
+    // The other game profile properties of the player, by name.
+    pub properties: HashMap<String, String>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -45,12 +49,13 @@ pub fn verify_forwarding_info(data: &[u8], secret: &[u8]) -> Result<ForwardingIn
     let addr: String = McBufReadable::read_from(&mut data)?;
     let uuid: uuid::Uuid = McBufReadable::read_from(&mut data)?;
     let name: String = McBufReadable::read_from(&mut data)?;
-    let skin = find_skin_data(&mut data)?;
+    let (skin, properties) = read_properties(&mut data)?;
     Ok(ForwardingInfo {
         addr: addr.parse()?,
         name,
         uuid,
         skin,
+        properties,
     })
 }
 
@@ -68,9 +73,12 @@ fn verify<'a>(data: &'a [u8], secret: &'a [u8]) -> Result<&'a [u8], VelocityErro
     Ok(data)
 }
 
-fn find_skin_data(
+/// Reads the game profile properties, separating the skin from the other properties.
+fn read_properties(
     data: &mut std::io::Cursor<&[u8]>,
-) -> Result<Option<skin::PlayerSkin>, VelocityError> {
+) -> Result<(Option<skin::PlayerSkin>, HashMap<String, String>), VelocityError> {
+    let mut skin = None;
+    let mut properties = HashMap::new();
     let len: i32 = McBufVarReadable::var_read_from(data)?;
     for _ in 0..len {
         let name: String = McBufReadable::read_from(data)?;
@@ -82,12 +90,14 @@ fn find_skin_data(
         }
 
         if name == skin::TEXTURE_KEY {
-            return Ok(Some(skin::PlayerSkin {
+            skin = Some(skin::PlayerSkin {
                 texture: value,
                 signature: maybe_sig.unwrap_or_default(),
                 layers: Default::default(),
-            }));
+            });
+        } else {
+            properties.insert(name, value);
         }
     }
-    Ok(None)
+    Ok((skin, properties))
 }
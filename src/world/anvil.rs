@@ -0,0 +1,318 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use azalea_block::BlockState;
+use azalea_core::position::{BlockPos, ChunkPos};
+use azalea_world::{palette::Palette, Chunk, Section};
+use simdnbt::owned::{Nbt, NbtCompound};
+use tracing::*;
+
+use super::{
+    blocks::{self, BlockResolver},
+    storage, World, WorldChunk,
+};
+
+/// Size of a sector in a region file, in bytes.
+const SECTOR_SIZE: usize = 4096;
+
+/// Loads the chunks within `radius` around `center` from a vanilla world folder.
+/// Chunks that have not been (fully) generated are left empty.
+#[tracing::instrument(skip(world, resolver))]
+pub fn load(
+    world: &mut World,
+    path: &str,
+    resolver: &BlockResolver,
+    center: ChunkPos,
+    radius: i32,
+) -> anyhow::Result<()> {
+    debug!("Loading world from region files");
+    let region_dir = Path::new(path).join("region");
+    if !region_dir.is_dir() {
+        anyhow::bail!("No region folder found in world {path}");
+    }
+
+    let mut loader = Loader {
+        resolver,
+        unknown_blocks: HashSet::new(),
+    };
+    let mut regions = HashMap::new();
+    for x in center.x - radius..=center.x + radius {
+        for z in center.z - radius..=center.z + radius {
+            let pos = ChunkPos::new(x, z);
+            let (region_x, region_z) = (x >> 5, z >> 5);
+            let region = match regions.entry((region_x, region_z)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let file = format!("r.{region_x}.{region_z}.mca");
+                    entry.insert(Region::open(region_dir.join(file))?)
+                }
+            };
+            let data = match region {
+                Some(region) => region.read_chunk(pos)?,
+                None => None,
+            };
+            let chunk = match data {
+                Some(data) => loader
+                    .read_chunk(world, &data)
+                    .context(format!("Failed to load chunk ({x}, {z})"))?,
+                None => None,
+            };
+            let chunk = chunk.unwrap_or_else(|| world.empty_chunk());
+            world.insert_chunk(pos, chunk);
+        }
+    }
+
+    if !loader.unknown_blocks.is_empty() {
+        warn!(blocks = ?loader.unknown_blocks, "Unknown blocks were replaced with air");
+    }
+    Ok(())
+}
+
+/// A region file, holding up to 32x32 chunks.
+struct Region {
+    path: PathBuf,
+    data: Vec<u8>,
+}
+
+impl Region {
+    /// Reads a region file, if it exists.
+    fn open(path: PathBuf) -> anyhow::Result<Option<Self>> {
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(Self { path, data })),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err).context(format!("Failed to read region file {}", path.display())),
+        }
+    }
+
+    /// Reads the decompressed NBT data of a chunk, if it has been generated.
+    fn read_chunk(&self, pos: ChunkPos) -> anyhow::Result<Option<Vec<u8>>> {
+        let index = ((pos.x & 31) + (pos.z & 31) * 32) as usize * 4;
+        let Some(entry) = self.data.get(index..index + 4) else {
+            return Ok(None);
+        };
+        let location = u32::from_be_bytes(entry.try_into()?);
+        if location == 0 {
+            return Ok(None);
+        }
+
+        let offset = (location >> 8) as usize * SECTOR_SIZE;
+        let header = self
+            .data
+            .get(offset..offset + 5)
+            .context("Chunk header out of bounds")?;
+        let length = u32::from_be_bytes(header[..4].try_into()?) as usize;
+        let compression = header[4];
+        if compression & 0x80 != 0 {
+            // Oversized chunks are stored in a separate file next to the region file
+            let path = self
+                .path
+                .with_file_name(format!("c.{}.{}.mcc", pos.x, pos.z));
+            let data = std::fs::read(&path)
+                .context(format!("Failed to read chunk file {}", path.display()))?;
+            return decompress(compression & 0x7F, &data).map(Some);
+        }
+        let data = self
+            .data
+            .get(offset + 5..offset + 4 + length)
+            .context("Chunk data out of bounds")?;
+        decompress(compression, data).map(Some)
+    }
+}
+
+fn decompress(compression: u8, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match compression {
+        1 => flate2::read::GzDecoder::new(data).read_to_end(&mut out)?,
+        2 => flate2::read::ZlibDecoder::new(data).read_to_end(&mut out)?,
+        3 => return Ok(data.to_vec()),
+        other => anyhow::bail!("Unsupported chunk compression type {other}"),
+    };
+    Ok(out)
+}
+
+struct Loader<'a> {
+    resolver: &'a BlockResolver,
+    /// Block names that could not be resolved
+    unknown_blocks: HashSet<String>,
+}
+
+impl Loader<'_> {
+    /// Converts the NBT data of a chunk, if it has been fully generated.
+    fn read_chunk(&mut self, world: &World, data: &[u8]) -> anyhow::Result<Option<WorldChunk>> {
+        let Nbt::Some(nbt) = Nbt::read(&mut Cursor::new(data))? else {
+            anyhow::bail!("Chunk data is empty");
+        };
+        let status = nbt.string("Status").map(|s| s.to_str()).unwrap_or_default();
+        if status.trim_start_matches("minecraft:") != "full" {
+            trace!(%status, "Skipping chunk that is not fully generated");
+            return Ok(None);
+        }
+
+        let mut sections = world.empty_chunk().chunk.sections;
+        for section in nbt
+            .list("sections")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+        {
+            let Some(y) = section.byte("Y") else {
+                continue;
+            };
+            // Sections just outside the world (used for lighting) are skipped here
+            let Some(target) = world
+                .dimension()
+                .section_index(y as i32 * 16)
+                .and_then(|index| sections.get_mut(index))
+            else {
+                continue;
+            };
+            if let Some(states) = section.compound("block_states") {
+                self.read_block_states(target, states);
+            }
+            if let Some(biomes) = section.compound("biomes") {
+                read_biomes(world, target, biomes);
+            }
+        }
+
+        let block_entities = nbt
+            .list("block_entities")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entity| {
+                let id = entity.string("id")?.to_str();
+                let pos = BlockPos::new(entity.int("x")?, entity.int("y")?, entity.int("z")?);
+                super::block_entity(&id, pos, entity)
+            })
+            .collect();
+
+        Ok(Some(WorldChunk {
+            chunk: Chunk {
+                sections,
+                heightmaps: Default::default(),
+            },
+            block_entities,
+            light: Default::default(),
+        }))
+    }
+
+    fn read_block_states(&mut self, section: &mut Section, nbt: &NbtCompound) {
+        let palette: Vec<u32> = nbt
+            .list("palette")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| {
+                let (name, properties) = blocks::read_palette_entry(entry);
+                self.resolver
+                    .resolve(&name, &properties)
+                    .unwrap_or_else(|| {
+                        self.unknown_blocks.insert(name);
+                        BlockState::AIR.id
+                    })
+            })
+            .collect();
+
+        match palette.as_slice() {
+            [] => {}
+            [single] => {
+                section.states.palette = Palette::SingleValue(*single);
+                if !self.resolver.is_air(*single) {
+                    section.block_count = 4096;
+                }
+            }
+            _ => {
+                let bits = storage::palette_bits(palette.len()).max(4);
+                let data = nbt.long_array("data").unwrap_or_default();
+                let mut block_count = 0;
+                for (i, index) in storage::unpack(data, bits, 4096).into_iter().enumerate() {
+                    let state = palette.get(index as usize).copied().unwrap_or_default();
+                    if !self.resolver.is_air(state) {
+                        block_count += 1;
+                    }
+                    section.states.set(i & 15, i >> 8, (i >> 4) & 15, state);
+                }
+                section.block_count = block_count;
+            }
+        }
+    }
+}
+
+fn read_biomes(world: &World, section: &mut Section, nbt: &NbtCompound) {
+    let palette: Vec<u32> = nbt
+        .list("palette")
+        .and_then(|list| list.strings())
+        .unwrap_or_default()
+        .iter()
+        .map(|name| world.biome_id(&name.to_str()))
+        .collect();
+
+    match palette.as_slice() {
+        [] => {}
+        [single] => section.biomes.palette = Palette::SingleValue(*single),
+        _ => {
+            let bits = storage::palette_bits(palette.len());
+            let data = nbt.long_array("data").unwrap_or_default();
+            for (i, index) in storage::unpack(data, bits, 64).into_iter().enumerate() {
+                let biome = palette.get(index as usize).copied().unwrap_or_default();
+                section.biomes.set(i & 3, i >> 4, (i >> 2) & 3, biome);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    /// Builds a region file holding a single chunk, stored with the given compression.
+    fn region_with_chunk(pos: ChunkPos, compression: u8, data: &[u8]) -> Region {
+        // The location and timestamp tables take up the first two sectors
+        let mut region = vec![0u8; SECTOR_SIZE * 2];
+        let index = ((pos.x & 31) + (pos.z & 31) * 32) as usize * 4;
+        let sectors = (data.len() + 5).div_ceil(SECTOR_SIZE) as u32;
+        region[index..index + 4].copy_from_slice(&((2 << 8) | sectors).to_be_bytes());
+        region.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+        region.push(compression);
+        region.extend_from_slice(data);
+        region.resize((2 + sectors as usize) * SECTOR_SIZE, 0);
+        Region {
+            path: PathBuf::from("r.0.0.mca"),
+            data: region,
+        }
+    }
+
+    #[test]
+    fn reads_zlib_chunk() {
+        let nbt = b"chunk data".repeat(100);
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(&nbt).unwrap();
+        let region = region_with_chunk(ChunkPos::new(3, 5), 2, &encoder.finish().unwrap());
+        assert_eq!(region.read_chunk(ChunkPos::new(3, 5)).unwrap(), Some(nbt));
+    }
+
+    #[test]
+    fn reads_uncompressed_chunk() {
+        let nbt = b"chunk data".to_vec();
+        // Chunk positions wrap around within their region
+        let region = region_with_chunk(ChunkPos::new(-1, 33), 3, &nbt);
+        assert_eq!(region.read_chunk(ChunkPos::new(-1, 33)).unwrap(), Some(nbt));
+    }
+
+    #[test]
+    fn missing_chunk_is_not_generated() {
+        let region = region_with_chunk(ChunkPos::new(0, 0), 3, b"chunk data");
+        assert_eq!(region.read_chunk(ChunkPos::new(1, 0)).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_compression() {
+        let region = region_with_chunk(ChunkPos::new(0, 0), 9, b"chunk data");
+        assert!(region.read_chunk(ChunkPos::new(0, 0)).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use azalea_block::BlockState;
+use azalea_core::resource_location::ResourceLocation;
+use simdnbt::owned::NbtCompound;
+use tracing::*;
+
+/// Block properties, sorted by name.
+pub type Properties = BTreeMap<String, String>;
+
+/// All states of a single block, as listed in the vanilla block report.
+struct BlockStates {
+    default: u32,
+    default_properties: Properties,
+    states: HashMap<Properties, u32>,
+}
+
+/// Resolves block names (and their properties) to block state IDs.
+pub struct BlockResolver {
+    /// Block states by block name, if a block report was loaded
+    report: Option<HashMap<String, BlockStates>>,
+    /// Block names and properties by state ID, if a block report was loaded
+    names: HashMap<u32, (String, Properties)>,
+    /// Block state IDs that are considered air
+    air: Vec<u32>,
+}
+
+#[derive(serde::Deserialize)]
+struct ReportBlock {
+    states: Vec<ReportState>,
+}
+
+#[derive(serde::Deserialize)]
+struct ReportState {
+    id: u32,
+    #[serde(default)]
+    default: bool,
+    #[serde(default)]
+    properties: Properties,
+}
+
+impl BlockResolver {
+    /// Creates a resolver, optionally backed by the vanilla `blocks.json` report.
+    /// Without a report, blocks always resolve to their default state.
+    pub fn load(report_path: Option<&str>) -> anyhow::Result<Self> {
+        let mut names = HashMap::new();
+        let report = match report_path {
+            Some(path) => {
+                debug!(path, "Loading block report");
+                let data = std::fs::read(path)
+                    .context(format!("Failed to read block report from {path}"))?;
+                let blocks: HashMap<String, ReportBlock> =
+                    serde_json::from_slice(&data).context("Failed to parse block report")?;
+                let blocks = blocks
+                    .into_iter()
+                    .filter_map(|(name, block)| {
+                        for state in &block.states {
+                            names.insert(state.id, (name.clone(), state.properties.clone()));
+                        }
+                        let default = block.states.iter().find(|s| s.default)?;
+                        let states = BlockStates {
+                            default: default.id,
+                            default_properties: default.properties.clone(),
+                            states: block
+                                .states
+                                .into_iter()
+                                .map(|s| (s.properties, s.id))
+                                .collect(),
+                        };
+                        Some((name, states))
+                    })
+                    .collect();
+                Some(blocks)
+            }
+            None => None,
+        };
+
+        let mut resolver = Self {
+            report,
+            names,
+            air: Vec::new(),
+        };
+        resolver.air = ["minecraft:air", "minecraft:cave_air", "minecraft:void_air"]
+            .iter()
+            .filter_map(|name| resolver.resolve(name, &Properties::new()))
+            .collect();
+        Ok(resolver)
+    }
+
+    /// Resolves a block name (e.g. `minecraft:oak_stairs`) and its properties to a block state ID.
+    /// Properties that are not given keep their default value.
+    pub fn resolve(&self, name: &str, properties: &Properties) -> Option<u32> {
+        let name = ResourceLocation::new(name).to_string();
+        let Some(report) = &self.report else {
+            let block = azalea_registry::Block::from_str(&name).ok()?;
+            return Some(BlockState::from(block).id);
+        };
+
+        let block = report.get(&name)?;
+        if properties.is_empty() {
+            return Some(block.default);
+        }
+        let mut merged = block.default_properties.clone();
+        merged.extend(properties.iter().map(|(k, v)| (k.clone(), v.clone())));
+        match block.states.get(&merged) {
+            Some(id) => Some(*id),
+            None => {
+                trace!(%name, ?properties, "Unknown block properties, using default state");
+                Some(block.default)
+            }
+        }
+    }
+
+    /// Parses a block state string (e.g. `minecraft:oak_stairs[facing=east,half=top]`)
+    /// and resolves it to a block state ID.
+    pub fn resolve_str(&self, state: &str) -> Option<u32> {
+        let (name, properties) = match state.split_once('[') {
+            Some((name, rest)) => {
+                let properties = rest
+                    .trim_end_matches(']')
+                    .split(',')
+                    .filter_map(|kv| kv.split_once('='))
+                    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                    .collect();
+                (name, properties)
+            }
+            None => (state, Properties::new()),
+        };
+        self.resolve(name, &properties)
+    }
+
+    /// Returns the name of the block a state ID belongs to (e.g. `minecraft:oak_stairs`).
+    pub fn name(&self, id: u32) -> Option<String> {
+        if let Some((name, _)) = self.names.get(&id) {
+            return Some(name.clone());
+        }
+        let state = BlockState::try_from(id).ok()?;
+        Some(azalea_registry::Block::from(state).to_string())
+    }
+
+    /// Returns the properties of a block state.
+    /// Without a block report, this is always `None`.
+    pub fn properties(&self, id: u32) -> Option<&Properties> {
+        self.names.get(&id).map(|(_, properties)| properties)
+    }
+
+    /// Returns whether the given block state ID is air.
+    pub fn is_air(&self, id: u32) -> bool {
+        id == BlockState::AIR.id || self.air.contains(&id)
+    }
+}
+
+/// Reads a block name and its properties from an NBT palette entry,
+/// as used by region files and structure files.
+pub fn read_palette_entry(entry: &NbtCompound) -> (String, Properties) {
+    let name = entry
+        .string("Name")
+        .map(|name| name.to_str().into_owned())
+        .unwrap_or_default();
+    let properties = entry
+        .compound("Properties")
+        .map(|props| {
+            props
+                .iter()
+                .filter_map(|(key, value)| {
+                    let value = value.string()?.to_str().into_owned();
+                    Some((key.to_str().into_owned(), value))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (name, properties)
+}
@@ -0,0 +1,74 @@
+use anyhow::Context;
+use azalea_core::position::{BlockPos, ChunkPos};
+use azalea_world::Chunk;
+use tracing::*;
+
+use super::{blocks::BlockResolver, World, WorldChunk};
+use crate::config::{
+    self,
+    ty::{GeneratorConfig, GeneratorKind},
+};
+
+/// Generates the chunks within `radius` around `center`, along with the spawn platform.
+#[tracing::instrument(skip_all)]
+pub fn generate(
+    world: &mut World,
+    config: &GeneratorConfig,
+    resolver: &BlockResolver,
+    center: ChunkPos,
+    radius: i32,
+) -> anyhow::Result<()> {
+    debug!(kind = ?config.kind, "Generating world");
+    let dimension = *world.dimension();
+
+    // Every generated chunk looks the same, so a single template is built and copied
+    let mut template = world.empty_chunk();
+    if let GeneratorKind::Flat { layers } = &config.kind {
+        let mut y = dimension.min_y;
+        for layer in layers {
+            let state = resolver
+                .resolve_str(&layer.block)
+                .context(format!("Unknown block {} in flat layer", layer.block))?;
+            for _ in 0..layer.height {
+                for x in 0..16 {
+                    for z in 0..16 {
+                        template.set_block(&dimension, BlockPos::new(x, y, z), state, resolver);
+                    }
+                }
+                y += 1;
+            }
+        }
+    }
+    for x in center.x - radius..=center.x + radius {
+        for z in center.z - radius..=center.z + radius {
+            let chunk = WorldChunk {
+                chunk: Chunk {
+                    sections: template.chunk.sections.clone(),
+                    heightmaps: Default::default(),
+                },
+                block_entities: Vec::new(),
+                light: Default::default(),
+            };
+            world.insert_chunk(ChunkPos::new(x, z), chunk);
+        }
+    }
+
+    if let Some(platform) = &config.platform {
+        let state = resolver
+            .resolve_str(&platform.block)
+            .context(format!("Unknown spawn platform block {}", platform.block))?;
+        let spawn = config::get().spawn_location.to_block_pos();
+        let size = platform.size as i32;
+        for dx in 0..size {
+            for dz in 0..size {
+                let pos = BlockPos::new(
+                    spawn.x - size / 2 + dx,
+                    spawn.y - 1,
+                    spawn.z - size / 2 + dz,
+                );
+                world.set_block(pos, state, resolver);
+            }
+        }
+    }
+    Ok(())
+}
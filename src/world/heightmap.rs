@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use azalea_world::{
+    heightmap::{Heightmap, HeightmapKind},
+    Chunk,
+};
+use tracing::*;
+
+use super::{blocks::BlockResolver, storage, Dimension, World};
+
+/// Blocks without a collision box that do not block motion.
+const PASSABLE_BLOCKS: &[&str] = &[
+    "allium",
+    "azure_bluet",
+    "beetroots",
+    "carrots",
+    "cave_vines",
+    "cave_vines_plant",
+    "cobweb",
+    "cornflower",
+    "dandelion",
+    "dead_bush",
+    "end_gateway",
+    "end_portal",
+    "fern",
+    "fire",
+    "glow_lichen",
+    "grass",
+    "hanging_roots",
+    "large_fern",
+    "lever",
+    "light",
+    "lilac",
+    "lily_of_the_valley",
+    "nether_portal",
+    "nether_sprouts",
+    "nether_wart",
+    "oxeye_daisy",
+    "peony",
+    "pitcher_plant",
+    "poppy",
+    "potatoes",
+    "powder_snow",
+    "redstone_wire",
+    "rose_bush",
+    "sculk_vein",
+    "short_grass",
+    "soul_fire",
+    "spore_blossom",
+    "structure_void",
+    "sugar_cane",
+    "sunflower",
+    "sweet_berry_bush",
+    "tall_grass",
+    "torchflower",
+    "tripwire",
+    "tripwire_hook",
+    "vine",
+    "wheat",
+    "wither_rose",
+];
+
+/// Suffixes of passable block families (e.g. all colors or wood types).
+const PASSABLE_SUFFIXES: &[&str] = &[
+    "_banner",
+    "_button",
+    "_coral",
+    "_coral_fan",
+    "_fungus",
+    "_mushroom",
+    "_pressure_plate",
+    "_roots",
+    "_sapling",
+    "_sign",
+    "_tulip",
+    "_vines",
+    "_vines_plant",
+    "blue_orchid",
+    "rail",
+    "torch",
+];
+
+/// Computes the `WORLD_SURFACE` and `MOTION_BLOCKING` heightmaps of every chunk in the world.
+#[tracing::instrument(skip_all)]
+pub fn compute(world: &mut World, resolver: &BlockResolver) {
+    debug!("Computing heightmaps");
+    let dimension = world.dimension;
+    let mut cache = HashMap::new();
+    for chunk in world.chunks.values_mut() {
+        let mut blocks_motion = |state: u32| {
+            *cache
+                .entry(state)
+                .or_insert_with(|| is_motion_blocking(resolver, state))
+        };
+        let (surface, motion_blocking): (Vec<u32>, Vec<u32>) = (0..256)
+            .map(|i| column_heights(&chunk.chunk, i & 15, i >> 4, resolver, &mut blocks_motion))
+            .unzip();
+        chunk.chunk.heightmaps = [
+            (HeightmapKind::WorldSurface, surface),
+            (HeightmapKind::MotionBlocking, motion_blocking),
+        ]
+        .into_iter()
+        .map(|(kind, heights)| (kind, heightmap(&dimension, kind, &heights)))
+        .collect();
+    }
+}
+
+/// Returns the heights of the highest non-air block and the highest motion-blocking block
+/// in a column, relative to the bottom of the world and plus one (0 if there is none).
+fn column_heights(
+    chunk: &Chunk,
+    x: usize,
+    z: usize,
+    resolver: &BlockResolver,
+    blocks_motion: &mut impl FnMut(u32) -> bool,
+) -> (u32, u32) {
+    let mut surface = None;
+    for (index, section) in chunk.sections.iter().enumerate().rev() {
+        if section.block_count == 0 {
+            continue;
+        }
+        for y in (0..16).rev() {
+            let state = section.states.get(x, y, z);
+            let height = (index * 16 + y) as u32 + 1;
+            if surface.is_none() && !resolver.is_air(state) {
+                surface = Some(height);
+            }
+            if blocks_motion(state) {
+                return (surface.unwrap_or(height), height);
+            }
+        }
+    }
+    (surface.unwrap_or_default(), 0)
+}
+
+/// Returns whether a block state blocks motion or contains a fluid.
+fn is_motion_blocking(resolver: &BlockResolver, state: u32) -> bool {
+    if resolver.is_air(state) {
+        return false;
+    }
+    let Some(name) = resolver.name(state) else {
+        return true;
+    };
+    let name = name.trim_start_matches("minecraft:");
+    let waterlogged = resolver
+        .properties(state)
+        .and_then(|p| p.get("waterlogged"))
+        .is_some_and(|w| w == "true");
+    waterlogged
+        || !(PASSABLE_BLOCKS.contains(&name) || PASSABLE_SUFFIXES.iter().any(|s| name.ends_with(s)))
+}
+
+/// Packs per-column heights into a heightmap.
+fn heightmap(dimension: &Dimension, kind: HeightmapKind, heights: &[u32]) -> Heightmap {
+    let bits = storage::palette_bits(dimension.height as usize + 1);
+    let data = storage::pack(heights, bits);
+    Heightmap::new(kind, dimension.height, dimension.min_y, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_core::position::{BlockPos, ChunkPos};
+
+    use super::*;
+
+    /// Returns the heights of every column in a heightmap of the chunk at the origin.
+    fn heights(world: &World, kind: HeightmapKind) -> Vec<u32> {
+        let chunk = &world.chunks[&ChunkPos::new(0, 0)].chunk;
+        let data: Vec<i64> = chunk.heightmaps[&kind]
+            .data
+            .data
+            .iter()
+            .map(|x| *x as i64)
+            .collect();
+        let bits = storage::palette_bits(world.dimension.height as usize + 1);
+        storage::unpack(&data, bits, 256)
+    }
+
+    #[test]
+    fn computes_heights_of_columns() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let dimension = Dimension {
+            min_y: -16,
+            height: 48,
+            has_skylight: true,
+        };
+        let mut world = World::new(dimension, 0);
+        let chunk = world.empty_chunk();
+        world.insert_chunk(ChunkPos::new(0, 0), chunk);
+        let mut set = |pos: BlockPos, name: &str| {
+            let state = resolver.resolve_str(name).unwrap();
+            assert!(world.set_block(pos, state, &resolver));
+        };
+        // A flat column with grass on top, which does not block motion
+        for y in -16..4 {
+            set(BlockPos::new(0, y, 0), "minecraft:stone");
+        }
+        set(BlockPos::new(0, 4, 0), "minecraft:short_grass");
+        // A fluid blocks motion, as does a block high up in the next section
+        set(BlockPos::new(1, 10, 0), "minecraft:water");
+        set(BlockPos::new(2, 31, 3), "minecraft:torch");
+        set(BlockPos::new(2, 20, 3), "minecraft:stone");
+        compute(&mut world, &resolver);
+
+        let surface = heights(&world, HeightmapKind::WorldSurface);
+        let motion_blocking = heights(&world, HeightmapKind::MotionBlocking);
+        // Heights are relative to the bottom of the world and point above the block
+        assert_eq!((surface[0], motion_blocking[0]), (21, 20));
+        assert_eq!((surface[1], motion_blocking[1]), (27, 27));
+        assert_eq!((surface[3 * 16 + 2], motion_blocking[3 * 16 + 2]), (48, 37));
+        assert_eq!((surface[16], motion_blocking[16]), (0, 0));
+        assert_eq!(surface[255], 0);
+    }
+}
@@ -0,0 +1,767 @@
+use std::collections::{HashMap, VecDeque};
+
+use azalea_core::{
+    bitset::BitSet,
+    position::{BlockPos, ChunkPos},
+};
+use azalea_protocol::packets::game::clientbound_light_update_packet::ClientboundLightUpdatePacketData;
+use azalea_world::palette::Palette;
+use tracing::*;
+
+use super::{blocks::BlockResolver, Dimension, World};
+use crate::config::ty::LightingMode;
+
+const MAX_LIGHT: u8 = 15;
+
+/// Light emitted by blocks, regardless of their state.
+const LIGHT_SOURCES: &[(&str, u8)] = &[
+    ("beacon", 15),
+    ("conduit", 15),
+    ("end_gateway", 15),
+    ("end_portal", 15),
+    ("fire", 15),
+    ("glowstone", 15),
+    ("jack_o_lantern", 15),
+    ("lantern", 15),
+    ("lava", 15),
+    ("lava_cauldron", 15),
+    ("ochre_froglight", 15),
+    ("pearlescent_froglight", 15),
+    ("sea_lantern", 15),
+    ("shroomlight", 15),
+    ("verdant_froglight", 15),
+    ("end_rod", 14),
+    ("torch", 14),
+    ("wall_torch", 14),
+    ("nether_portal", 11),
+    ("crying_obsidian", 10),
+    ("soul_fire", 10),
+    ("soul_lantern", 10),
+    ("soul_torch", 10),
+    ("soul_wall_torch", 10),
+    ("enchanting_table", 7),
+    ("ender_chest", 7),
+    ("glow_lichen", 7),
+    ("amethyst_cluster", 5),
+    ("large_amethyst_bud", 4),
+    ("magma_block", 3),
+    ("medium_amethyst_bud", 2),
+    ("brewing_stand", 1),
+    ("brown_mushroom", 1),
+    ("calibrated_sculk_sensor", 1),
+    ("dragon_egg", 1),
+    ("end_portal_frame", 1),
+    ("sculk_sensor", 1),
+    ("small_amethyst_bud", 1),
+];
+
+/// Light emitted by blocks while they are lit, along with whether they are lit by default.
+const LIT_LIGHT_SOURCES: &[(&str, u8, bool)] = &[
+    ("campfire", 15, true),
+    ("redstone_lamp", 15, false),
+    ("blast_furnace", 13, false),
+    ("furnace", 13, false),
+    ("smoker", 13, false),
+    ("soul_campfire", 10, true),
+    ("deepslate_redstone_ore", 9, false),
+    ("redstone_ore", 9, false),
+    ("redstone_torch", 7, true),
+    ("redstone_wall_torch", 7, true),
+];
+
+/// Blocks that let light pass through unchanged.
+const TRANSPARENT_BLOCKS: &[&str] = &[
+    "allium",
+    "anvil",
+    "azure_bluet",
+    "barrier",
+    "beacon",
+    "beetroots",
+    "bell",
+    "brewing_stand",
+    "cactus",
+    "cake",
+    "carrots",
+    "cauldron",
+    "chain",
+    "comparator",
+    "conduit",
+    "cornflower",
+    "dandelion",
+    "daylight_detector",
+    "dead_bush",
+    "decorated_pot",
+    "dragon_egg",
+    "enchanting_table",
+    "end_portal_frame",
+    "end_rod",
+    "fern",
+    "fire",
+    "flower_pot",
+    "grindstone",
+    "hopper",
+    "kelp",
+    "ladder",
+    "lantern",
+    "large_fern",
+    "lectern",
+    "lever",
+    "light",
+    "lightning_rod",
+    "lilac",
+    "lily_of_the_valley",
+    "lily_pad",
+    "nether_wart",
+    "oxeye_daisy",
+    "peony",
+    "pitcher_plant",
+    "pointed_dripstone",
+    "poppy",
+    "potatoes",
+    "redstone_wire",
+    "repeater",
+    "rose_bush",
+    "scaffolding",
+    "sea_pickle",
+    "seagrass",
+    "short_grass",
+    "snow",
+    "soul_fire",
+    "soul_lantern",
+    "spawner",
+    "stonecutter",
+    "structure_void",
+    "sugar_cane",
+    "sunflower",
+    "sweet_berry_bush",
+    "tall_grass",
+    "tall_seagrass",
+    "torchflower",
+    "tripwire",
+    "tripwire_hook",
+    "vine",
+    "wheat",
+    "wither_rose",
+];
+
+/// Name suffixes of blocks that let light pass through unchanged.
+const TRANSPARENT_SUFFIXES: &[&str] = &[
+    "air",
+    "_amethyst_bud",
+    "amethyst_cluster",
+    "_banner",
+    "_bars",
+    "_bed",
+    "_button",
+    "candle",
+    "candle_cake",
+    "carpet",
+    "chest",
+    "coral",
+    "coral_fan",
+    "door",
+    "_fence",
+    "_fence_gate",
+    "_fungus",
+    "glass",
+    "glass_pane",
+    "_head",
+    "_mushroom",
+    "_orchid",
+    "_plant",
+    "_pressure_plate",
+    "rail",
+    "_roots",
+    "_sapling",
+    "_sign",
+    "_skull",
+    "_slab",
+    "_stairs",
+    "torch",
+    "_tulip",
+    "vines",
+    "_wall",
+];
+
+/// Blocks that block light, even though their name ends with a transparent suffix.
+const OPAQUE_BLOCKS: &[&str] = &["tinted_glass"];
+
+/// Blocks that let light through, but dim it by one level (e.g. water and leaves).
+const DIFFUSING_BLOCKS: &[&str] = &[
+    "bubble_column",
+    "cobweb",
+    "frosted_ice",
+    "honey_block",
+    "ice",
+    "slime_block",
+    "water",
+];
+
+/// How a block interacts with light.
+#[derive(Clone, Copy)]
+struct LightProps {
+    /// How much light is lost when passing through the block
+    opacity: u8,
+    /// How much light the block emits
+    emission: u8,
+}
+
+impl LightProps {
+    fn of(resolver: &BlockResolver, state: u32) -> Self {
+        if resolver.is_air(state) {
+            return Self {
+                opacity: 0,
+                emission: 0,
+            };
+        }
+        let Some(name) = resolver.name(state) else {
+            return Self {
+                opacity: MAX_LIGHT,
+                emission: 0,
+            };
+        };
+        let name = name.trim_start_matches("minecraft:");
+        let properties = resolver.properties(state);
+        let property = |key: &str| properties.and_then(|p| p.get(key)).map(String::as_str);
+
+        let emission = if let Some((_, level)) = LIGHT_SOURCES.iter().find(|(n, _)| *n == name) {
+            *level
+        } else if let Some((_, level, lit_by_default)) =
+            LIT_LIGHT_SOURCES.iter().find(|(n, ..)| *n == name)
+        {
+            let lit = property("lit").map_or(*lit_by_default, |lit| lit == "true");
+            if lit {
+                *level
+            } else {
+                0
+            }
+        } else if name == "light" {
+            property("level")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(MAX_LIGHT)
+        } else if name.ends_with("candle") && property("lit") == Some("true") {
+            let candles: u8 = property("candles")
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(1);
+            candles * 3
+        } else {
+            0
+        };
+
+        let opacity = if OPAQUE_BLOCKS.contains(&name) {
+            MAX_LIGHT
+        } else if DIFFUSING_BLOCKS.contains(&name) || name.ends_with("_leaves") {
+            1
+        } else if TRANSPARENT_BLOCKS.contains(&name)
+            || TRANSPARENT_SUFFIXES.iter().any(|s| name.ends_with(s))
+        {
+            // Waterlogged blocks dim light just like water does
+            u8::from(property("waterlogged") == Some("true"))
+        } else {
+            MAX_LIGHT
+        };
+
+        Self { opacity, emission }
+    }
+}
+
+/// Light levels of a 16x16x16 section.
+#[derive(Clone)]
+enum LightSection {
+    /// Every block has the same light level.
+    Uniform(u8),
+    /// Light levels, indexed by `y << 8 | z << 4 | x`.
+    Data(Box<[u8; 4096]>),
+}
+
+impl LightSection {
+    fn get(&self, index: usize) -> u8 {
+        match self {
+            Self::Uniform(level) => *level,
+            Self::Data(data) => data[index],
+        }
+    }
+
+    fn set(&mut self, index: usize, level: u8) {
+        match self {
+            Self::Uniform(current) if *current == level => {}
+            Self::Uniform(current) => {
+                let mut data = Box::new([*current; 4096]);
+                data[index] = level;
+                *self = Self::Data(data);
+            }
+            Self::Data(data) => data[index] = level,
+        }
+    }
+
+    /// Collapses the section into a uniform one if every block has the same light level.
+    fn compact(&mut self) {
+        if let Self::Data(data) = self {
+            let first = data[0];
+            if data.iter().all(|level| *level == first) {
+                *self = Self::Uniform(first);
+            }
+        }
+    }
+
+    /// Packs the light levels into nibbles, or returns `None` if the section is completely dark.
+    fn to_nibbles(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Uniform(0) => None,
+            Self::Uniform(level) => Some(vec![level | level << 4; 2048]),
+            Self::Data(data) => Some(
+                data.chunks_exact(2)
+                    .map(|pair| pair[0] | pair[1] << 4)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Sky and block light of a chunk column.
+/// Contains one extra section below and above the world, as expected by the client.
+#[derive(Clone, Default)]
+pub struct ChunkLight {
+    sky: Vec<LightSection>,
+    block: Vec<LightSection>,
+}
+
+impl ChunkLight {
+    /// Creates light data where every block is fully lit.
+    fn fullbright(count: usize) -> Self {
+        Self {
+            sky: vec![LightSection::Uniform(MAX_LIGHT); count],
+            block: vec![LightSection::Uniform(MAX_LIGHT); count],
+        }
+    }
+
+    /// Creates light data for a chunk without any blocks in it.
+    pub fn empty(dimension: &Dimension, mode: LightingMode) -> Self {
+        let count = dimension.section_count() + 2;
+        match mode {
+            LightingMode::Fullbright => Self::fullbright(count),
+            LightingMode::Computed => {
+                let sky = if dimension.has_skylight { MAX_LIGHT } else { 0 };
+                Self {
+                    sky: vec![LightSection::Uniform(sky); count],
+                    block: vec![LightSection::Uniform(0); count],
+                }
+            }
+        }
+    }
+
+    /// Creates light data where every block is completely dark.
+    fn dark(count: usize) -> Self {
+        Self {
+            sky: vec![LightSection::Uniform(0); count],
+            block: vec![LightSection::Uniform(0); count],
+        }
+    }
+
+    fn sections(&self, sky: bool) -> &[LightSection] {
+        if sky {
+            &self.sky
+        } else {
+            &self.block
+        }
+    }
+
+    fn sections_mut(&mut self, sky: bool) -> &mut Vec<LightSection> {
+        if sky {
+            &mut self.sky
+        } else {
+            &mut self.block
+        }
+    }
+
+    /// Builds the light data that is sent along with the chunk.
+    pub fn to_packet_data(&self) -> ClientboundLightUpdatePacketData {
+        let count = self.sky.len();
+        let mut data = ClientboundLightUpdatePacketData {
+            sky_y_mask: BitSet::new(count),
+            block_y_mask: BitSet::new(count),
+            empty_sky_y_mask: BitSet::new(count),
+            empty_block_y_mask: BitSet::new(count),
+            sky_updates: Vec::new(),
+            block_updates: Vec::new(),
+        };
+        for (i, section) in self.sky.iter().enumerate() {
+            match section.to_nibbles() {
+                Some(nibbles) => {
+                    data.sky_y_mask.set(i);
+                    data.sky_updates.push(nibbles);
+                }
+                None => data.empty_sky_y_mask.set(i),
+            }
+        }
+        for (i, section) in self.block.iter().enumerate() {
+            match section.to_nibbles() {
+                Some(nibbles) => {
+                    data.block_y_mask.set(i);
+                    data.block_updates.push(nibbles);
+                }
+                None => data.empty_block_y_mask.set(i),
+            }
+        }
+        data
+    }
+}
+
+/// Computes the light of every chunk in the world.
+#[tracing::instrument(skip(world, resolver))]
+pub fn compute(world: &mut World, resolver: &BlockResolver, mode: LightingMode) {
+    debug!("Computing world light");
+    let count = world.dimension.section_count() + 2;
+    let light = match mode {
+        LightingMode::Fullbright => {
+            for chunk in world.chunks.values_mut() {
+                chunk.light = ChunkLight::fullbright(count);
+            }
+            return;
+        }
+        LightingMode::Computed => {
+            let mut engine = Engine {
+                world,
+                resolver,
+                props: HashMap::new(),
+                light: HashMap::new(),
+                min_y: world.dimension.min_y - 16,
+                count,
+            };
+            if world.dimension.has_skylight {
+                engine.light_sky();
+            } else {
+                engine.light = world
+                    .chunks
+                    .keys()
+                    .map(|pos| (*pos, ChunkLight::dark(count)))
+                    .collect();
+            }
+            engine.light_blocks();
+            engine.light
+        }
+    };
+
+    for (pos, mut light) in light {
+        light.sky.iter_mut().for_each(LightSection::compact);
+        light.block.iter_mut().for_each(LightSection::compact);
+        if let Some(chunk) = world.chunks.get_mut(&pos) {
+            chunk.light = light;
+        }
+    }
+}
+
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+struct Engine<'a> {
+    world: &'a World,
+    resolver: &'a BlockResolver,
+    /// Light properties by block state ID
+    props: HashMap<u32, LightProps>,
+    light: HashMap<ChunkPos, ChunkLight>,
+    /// The bottom of the lowest light section
+    min_y: i32,
+    /// The amount of light sections in a chunk column
+    count: usize,
+}
+
+impl Engine<'_> {
+    fn props(&mut self, state: u32) -> LightProps {
+        let resolver = self.resolver;
+        *self
+            .props
+            .entry(state)
+            .or_insert_with(|| LightProps::of(resolver, state))
+    }
+
+    /// Returns the block state at the given position.
+    /// Positions outside of the world's height range are considered air.
+    fn block(&self, pos: &BlockPos) -> u32 {
+        let chunk = self
+            .world
+            .chunks
+            .get(&ChunkPos::new(pos.x >> 4, pos.z >> 4));
+        let section = self
+            .world
+            .dimension
+            .section_index(pos.y)
+            .and_then(|index| chunk?.chunk.sections.get(index));
+        match section {
+            Some(section) => section.states.get(
+                (pos.x & 15) as usize,
+                (pos.y & 15) as usize,
+                (pos.z & 15) as usize,
+            ),
+            None => azalea_block::BlockState::AIR.id,
+        }
+    }
+
+    /// Returns the chunk, section and block index of a position.
+    fn index(&self, pos: &BlockPos) -> Option<(ChunkPos, usize, usize)> {
+        let y = pos.y - self.min_y;
+        let section = usize::try_from(y >> 4)
+            .ok()
+            .filter(|section| *section < self.count)?;
+        let index = ((y & 15) << 8 | (pos.z & 15) << 4 | (pos.x & 15)) as usize;
+        Some((ChunkPos::new(pos.x >> 4, pos.z >> 4), section, index))
+    }
+
+    /// Returns the light level at a position, or `None` if it is outside of the world.
+    fn get(&self, pos: &BlockPos, sky: bool) -> Option<u8> {
+        let (chunk, section, index) = self.index(pos)?;
+        Some(self.light.get(&chunk)?.sections(sky)[section].get(index))
+    }
+
+    fn set(&mut self, pos: &BlockPos, level: u8, sky: bool) {
+        let Some((chunk, section, index)) = self.index(pos) else {
+            return;
+        };
+        if let Some(light) = self.light.get_mut(&chunk) {
+            light.sections_mut(sky)[section].set(index, level);
+        }
+    }
+
+    /// Lights every column from the sky down, then spreads the light sideways.
+    fn light_sky(&mut self) {
+        let world = self.world;
+        let mut surfaces = HashMap::new();
+
+        for (pos, chunk) in &world.chunks {
+            // Sections above the highest non-empty section are lit by the sky completely
+            let top = chunk
+                .chunk
+                .sections
+                .iter()
+                .rposition(|section| section.block_count > 0)
+                .map_or(0, |index| index + 2);
+            let mut light = ChunkLight::dark(self.count);
+            for section in &mut light.sky[top..] {
+                *section = LightSection::Uniform(MAX_LIGHT);
+            }
+            self.light.insert(*pos, light);
+
+            let top_y = self.min_y + top as i32 * 16;
+            for x in 0..16 {
+                for z in 0..16 {
+                    let (x, z) = (pos.x * 16 + x, pos.z * 16 + z);
+                    let mut level = MAX_LIGHT;
+                    let mut surface = self.min_y;
+                    for y in (self.min_y..top_y).rev() {
+                        let block_pos = BlockPos::new(x, y, z);
+                        let state = self.block(&block_pos);
+                        level = level.saturating_sub(self.props(state).opacity);
+                        if level == 0 {
+                            surface = y + 1;
+                            break;
+                        }
+                        self.set(&block_pos, level, true);
+                    }
+                    surfaces.insert((x, z), surface);
+                }
+            }
+        }
+
+        // Light only needs to spread from the parts of columns that neighbor darker columns
+        let mut queue = VecDeque::new();
+        for (&(x, z), &surface) in &surfaces {
+            let highest_neighbor = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .filter_map(|(dx, dz)| surfaces.get(&(x + dx, z + dz)))
+                .max()
+                .copied()
+                .unwrap_or(surface);
+            for y in surface..=highest_neighbor.max(surface) {
+                let pos = BlockPos::new(x, y, z);
+                if let Some(level) = self.get(&pos, true).filter(|level| *level > 1) {
+                    queue.push_back((pos, level));
+                }
+            }
+        }
+        self.propagate(queue, true);
+    }
+
+    /// Finds every light-emitting block and spreads its light.
+    fn light_blocks(&mut self) {
+        let world = self.world;
+        let mut queue = VecDeque::new();
+        for (pos, chunk) in &world.chunks {
+            for (index, section) in chunk.chunk.sections.iter().enumerate() {
+                // Skip sections that cannot contain any light sources
+                let has_source = match &section.states.palette {
+                    Palette::SingleValue(state) => self.props(*state).emission > 0,
+                    Palette::Linear(states) | Palette::Hashmap(states) => {
+                        states.iter().any(|state| self.props(*state).emission > 0)
+                    }
+                    Palette::Global => true,
+                };
+                if !has_source {
+                    continue;
+                }
+
+                let base_y = world.dimension.min_y + index as i32 * 16;
+                for i in 0..4096 {
+                    let (x, y, z) = (i & 15, i >> 8, (i >> 4) & 15);
+                    let emission = self.props(section.states.get(x, y, z)).emission;
+                    if emission > 0 {
+                        let block_pos = BlockPos::new(
+                            pos.x * 16 + x as i32,
+                            base_y + y as i32,
+                            pos.z * 16 + z as i32,
+                        );
+                        self.set(&block_pos, emission, false);
+                        queue.push_back((block_pos, emission));
+                    }
+                }
+            }
+        }
+        self.propagate(queue, false);
+    }
+
+    /// Spreads light from the queued positions to their neighbors.
+    fn propagate(&mut self, mut queue: VecDeque<(BlockPos, u8)>, sky: bool) {
+        while let Some((pos, level)) = queue.pop_front() {
+            for (dx, dy, dz) in DIRECTIONS {
+                let next = BlockPos::new(pos.x + dx, pos.y + dy, pos.z + dz);
+                let Some(current) = self.get(&next, sky) else {
+                    continue;
+                };
+                let state = self.block(&next);
+                let opacity = self.props(state).opacity.max(1);
+                let new = level.saturating_sub(opacity);
+                if new > current {
+                    self.set(&next, new, sky);
+                    if new > 1 {
+                        queue.push_back((next, new));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world(height: u32, has_skylight: bool) -> World {
+        let dimension = Dimension {
+            min_y: 0,
+            height,
+            has_skylight,
+        };
+        let mut world = World::new(dimension, 0);
+        let chunk = world.empty_chunk();
+        world.insert_chunk(ChunkPos::new(0, 0), chunk);
+        world
+    }
+
+    fn state(resolver: &BlockResolver, name: &str) -> u32 {
+        resolver.resolve_str(name).unwrap()
+    }
+
+    fn props(resolver: &BlockResolver, name: &str) -> (u8, u8) {
+        let props = LightProps::of(resolver, state(resolver, name));
+        (props.opacity, props.emission)
+    }
+
+    /// Returns the computed light level at a position in the chunk at the origin.
+    fn level(world: &World, pos: BlockPos, sky: bool) -> u8 {
+        let light = &world.chunks[&ChunkPos::new(0, 0)].light;
+        let y = pos.y - world.dimension.min_y + 16;
+        let index = ((y & 15) << 8 | (pos.z & 15) << 4 | (pos.x & 15)) as usize;
+        light.sections(sky)[(y >> 4) as usize].get(index)
+    }
+
+    #[test]
+    fn classifies_opacity() {
+        let resolver = BlockResolver::load(None).unwrap();
+        assert_eq!(props(&resolver, "minecraft:air"), (0, 0));
+        assert_eq!(props(&resolver, "minecraft:stone"), (15, 0));
+        assert_eq!(props(&resolver, "minecraft:glass"), (0, 0));
+        assert_eq!(props(&resolver, "minecraft:red_stained_glass_pane"), (0, 0));
+        assert_eq!(props(&resolver, "minecraft:tinted_glass"), (15, 0));
+        assert_eq!(props(&resolver, "minecraft:water"), (1, 0));
+        assert_eq!(props(&resolver, "minecraft:oak_leaves"), (1, 0));
+        assert_eq!(props(&resolver, "minecraft:oak_stairs"), (0, 0));
+        assert_eq!(props(&resolver, "minecraft:torch"), (0, 14));
+        assert_eq!(props(&resolver, "minecraft:glowstone"), (15, 15));
+    }
+
+    #[test]
+    fn sky_light_stops_at_opaque_blocks() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let stone = state(&resolver, "minecraft:stone");
+        let mut world = world(32, true);
+        world.set_block(BlockPos::new(4, 20, 4), stone, &resolver);
+        compute(&mut world, &resolver, LightingMode::Computed);
+
+        assert_eq!(level(&world, BlockPos::new(4, 21, 4), true), 15);
+        assert_eq!(level(&world, BlockPos::new(4, 20, 4), true), 0);
+        // Light spreads in from the neighboring columns
+        assert_eq!(level(&world, BlockPos::new(4, 19, 4), true), 14);
+        assert_eq!(level(&world, BlockPos::new(5, 19, 4), true), 15);
+        assert_eq!(level(&world, BlockPos::new(4, 0, 4), true), 14);
+    }
+
+    #[test]
+    fn torch_light_falls_off_around_walls() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let mut world = world(16, false);
+        world.set_block(
+            BlockPos::new(8, 8, 8),
+            state(&resolver, "minecraft:torch"),
+            &resolver,
+        );
+        world.set_block(
+            BlockPos::new(8, 8, 10),
+            state(&resolver, "minecraft:stone"),
+            &resolver,
+        );
+        compute(&mut world, &resolver, LightingMode::Computed);
+
+        assert_eq!(level(&world, BlockPos::new(8, 8, 8), false), 14);
+        assert_eq!(level(&world, BlockPos::new(9, 8, 8), false), 13);
+        assert_eq!(level(&world, BlockPos::new(8, 3, 8), false), 9);
+        assert_eq!(level(&world, BlockPos::new(8, 8, 10), false), 0);
+        // Behind the wall, light has to go around it
+        assert_eq!(level(&world, BlockPos::new(8, 8, 11), false), 9);
+        assert_eq!(level(&world, BlockPos::new(8, 8, 8), true), 0);
+    }
+
+    #[test]
+    fn packet_data_masks_empty_sections() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let stone = state(&resolver, "minecraft:stone");
+        let mut world = world(16, true);
+        for x in 0..16 {
+            for z in 0..16 {
+                world.set_block(BlockPos::new(x, 8, z), stone, &resolver);
+            }
+        }
+        compute(&mut world, &resolver, LightingMode::Computed);
+
+        let data = world.chunks[&ChunkPos::new(0, 0)].light.to_packet_data();
+        // The section below the world is dark, the roofed section is partially lit
+        // and the section above the world is lit completely
+        assert!(data.empty_sky_y_mask.index(0));
+        assert!(data.sky_y_mask.index(1) && data.sky_y_mask.index(2));
+        assert!(!data.sky_y_mask.index(0));
+        assert_eq!(data.sky_updates.len(), 2);
+        let roofed = &data.sky_updates[0];
+        assert_eq!(roofed.len(), 2048);
+        assert_eq!(roofed[(7 << 8) / 2], 0);
+        assert_eq!(roofed[(8 << 8) / 2], 0);
+        assert_eq!(roofed[(9 << 8) / 2], 0xFF);
+        assert!(data.sky_updates[1].iter().all(|nibbles| *nibbles == 0xFF));
+
+        assert!((0..3).all(|i| data.empty_block_y_mask.index(i)));
+        assert!(data.block_updates.is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::Context;
+use azalea_core::position::{BlockPos, ChunkPos};
+use azalea_protocol::packets::game::clientbound_level_chunk_with_light_packet::BlockEntity;
+use azalea_world::{palette::Palette, Chunk, Section};
+use simdnbt::{
+    owned::{Nbt, NbtCompound},
+    Serialize,
+};
+use tracing::*;
+
+use crate::{
+    config::{self, ty::WorldSource},
+    utils::registry_data,
+};
+
+pub mod anvil;
+pub mod blocks;
+pub mod generator;
+pub mod heightmap;
+pub mod light;
+pub mod schematic;
+pub mod storage;
+
+/// The name of the world's dimension, as sent to the client.
+pub const DIMENSION_NAME: &str = "minecraft:world";
+const DIMENSION_TYPE_REGISTRY: &str = "minecraft:dimension_type";
+const BIOME_REGISTRY: &str = "minecraft:worldgen/biome";
+
+static mut WORLD: Option<World> = None;
+
+/// A chunk column, along with the block entities in it.
+pub struct WorldChunk {
+    pub chunk: Chunk,
+    pub block_entities: Vec<BlockEntity>,
+    pub light: light::ChunkLight,
+}
+
+impl WorldChunk {
+    /// Sets the block state at the given position, which must be within this chunk column.
+    /// Blocks outside of the dimension's height range are ignored.
+    pub fn set_block(
+        &mut self,
+        dimension: &Dimension,
+        pos: BlockPos,
+        state: u32,
+        resolver: &blocks::BlockResolver,
+    ) {
+        let Some(section) = dimension
+            .section_index(pos.y)
+            .and_then(|index| self.chunk.sections.get_mut(index))
+        else {
+            return;
+        };
+        let (x, y, z) = (
+            (pos.x & 15) as usize,
+            (pos.y & 15) as usize,
+            (pos.z & 15) as usize,
+        );
+        let old = section.states.get(x, y, z);
+        section.states.set(x, y, z, state);
+        match (resolver.is_air(old), resolver.is_air(state)) {
+            (true, false) => section.block_count += 1,
+            (false, true) => section.block_count -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// The vertical bounds of a dimension type.
+#[derive(Debug, Clone, Copy)]
+pub struct Dimension {
+    /// The lowest Y coordinate of the world
+    pub min_y: i32,
+    /// The height of the world in blocks
+    pub height: u32,
+    /// Whether the dimension is lit by the sky
+    pub has_skylight: bool,
+}
+
+impl Dimension {
+    /// Reads the bounds of a dimension type from the registry data.
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        let element = registry_data::entry_element(DIMENSION_TYPE_REGISTRY, name)
+            .context(format!("Unknown dimension type {name}"))?;
+        let min_y = element
+            .int("min_y")
+            .context(format!("Dimension type {name} has no min_y"))?;
+        let height = element
+            .int("height")
+            .context(format!("Dimension type {name} has no height"))?;
+        if min_y % 16 != 0 || height <= 0 || height % 16 != 0 {
+            anyhow::bail!("Dimension type {name} has bounds that are not aligned to sections");
+        }
+        let has_skylight = element.byte("has_skylight").unwrap_or(1) != 0;
+        Ok(Self {
+            min_y,
+            height: height as u32,
+            has_skylight,
+        })
+    }
+
+    /// Returns the amount of sections in a chunk column.
+    pub fn section_count(&self) -> usize {
+        self.height as usize / 16
+    }
+
+    /// Returns the index of the section containing the given Y coordinate, if it is in bounds.
+    pub fn section_index(&self, y: i32) -> Option<usize> {
+        usize::try_from((y - self.min_y) >> 4)
+            .ok()
+            .filter(|index| *index < self.section_count())
+    }
+}
+
+/// The world that is sent to players.
+pub struct World {
+    dimension: Dimension,
+    /// The biome of chunks that do not specify one
+    biome: u32,
+    chunks: HashMap<ChunkPos, WorldChunk>,
+}
+
+impl World {
+    /// Creates a world without any chunks.
+    pub fn new(dimension: Dimension, biome: u32) -> Self {
+        Self {
+            dimension,
+            biome,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Returns the dimension bounds of the world.
+    pub fn dimension(&self) -> &Dimension {
+        &self.dimension
+    }
+
+    /// Resolves a biome name to its ID in the biome registry, falling back to the world's biome.
+    pub fn biome_id(&self, name: &str) -> u32 {
+        registry_data::entry_id(BIOME_REGISTRY, name).unwrap_or(self.biome)
+    }
+
+    /// Creates a chunk containing nothing but air.
+    pub fn empty_chunk(&self) -> WorldChunk {
+        let mut section = Section::default();
+        section.biomes.palette = Palette::SingleValue(self.biome);
+        WorldChunk {
+            chunk: Chunk {
+                sections: vec![section; self.dimension.section_count()],
+                heightmaps: Default::default(),
+            },
+            block_entities: Vec::new(),
+            light: Default::default(),
+        }
+    }
+
+    /// Returns all loaded chunks.
+    pub fn chunks(&self) -> impl Iterator<Item = (&ChunkPos, &WorldChunk)> {
+        self.chunks.iter()
+    }
+
+    /// Inserts a chunk, replacing any chunk at the same position.
+    pub fn insert_chunk(&mut self, pos: ChunkPos, chunk: WorldChunk) {
+        self.chunks.insert(pos, chunk);
+    }
+
+    /// Returns the chunk containing the given block, if it is loaded.
+    fn chunk_at_mut(&mut self, pos: &BlockPos) -> Option<&mut WorldChunk> {
+        self.chunks.get_mut(&ChunkPos::new(pos.x >> 4, pos.z >> 4))
+    }
+
+    /// Sets the block state at the given position, returning whether its chunk is loaded.
+    /// Only the chunks within the world's radius are loaded, so blocks beyond it are clipped
+    /// (those chunks are sent empty). Blocks outside of the world's height range are ignored.
+    pub fn set_block(
+        &mut self,
+        pos: BlockPos,
+        state: u32,
+        resolver: &blocks::BlockResolver,
+    ) -> bool {
+        let dimension = self.dimension;
+        let Some(chunk) = self.chunk_at_mut(&pos) else {
+            return false;
+        };
+        chunk.set_block(&dimension, pos, state, resolver);
+        true
+    }
+
+    /// Returns the block state at the given position, if its chunk is loaded and it is in bounds.
+    #[cfg(test)]
+    pub fn block_at(&self, pos: BlockPos) -> Option<u32> {
+        let chunk = self.chunks.get(&ChunkPos::new(pos.x >> 4, pos.z >> 4))?;
+        let section = &chunk.chunk.sections[self.dimension.section_index(pos.y)?];
+        let (x, y, z) = (pos.x & 15, pos.y & 15, pos.z & 15);
+        Some(section.states.get(x as usize, y as usize, z as usize))
+    }
+
+    /// Sets a block entity, replacing any block entity at the same position.
+    /// Like blocks, block entities in chunks that are not loaded are clipped.
+    pub fn set_block_entity(&mut self, pos: BlockPos, entity: BlockEntity) {
+        let Some(chunk) = self.chunk_at_mut(&pos) else {
+            return;
+        };
+        chunk
+            .block_entities
+            .retain(|e| e.packed_xz != entity.packed_xz || e.y != entity.y);
+        chunk.block_entities.push(entity);
+    }
+}
+
+pub fn init() -> anyhow::Result<()> {
+    info!("Initializing world");
+
+    let config = config::get();
+    let resolver = blocks::BlockResolver::load(config.world.block_report.as_deref())?;
+    let generator = &config.world.generator;
+    let dimension = Dimension::load(&config.world.dimension_type)?;
+    debug!(?dimension, "Loaded dimension type");
+    let spawn_y = config.spawn_location.to_block_pos().y;
+    if dimension.section_index(spawn_y).is_none() {
+        warn!(
+            spawn_y,
+            "Spawn location is outside of the dimension's height range"
+        );
+    }
+    let biome = registry_data::entry_id(BIOME_REGISTRY, &generator.biome)
+        .context(format!("Unknown biome {}", generator.biome))?;
+
+    let mut world = World::new(dimension, biome);
+    let center = spawn_chunk();
+    let radius = config.world.radius as i32;
+    match &config.world.source {
+        WorldSource::Generated => {
+            generator::generate(&mut world, generator, &resolver, center, radius)?
+        }
+        WorldSource::Anvil { path } => anvil::load(&mut world, path, &resolver, center, radius)?,
+        WorldSource::Schematic { path, origin } => {
+            generator::generate(&mut world, generator, &resolver, center, radius)?;
+            schematic::paste(&mut world, path, origin.to_block_pos(), &resolver)?;
+        }
+    }
+    heightmap::compute(&mut world, &resolver);
+    light::compute(&mut world, &resolver, config.world.lighting);
+    debug!(chunks = world.chunks.len(), "World loaded");
+
+    unsafe { WORLD = Some(world) };
+    Ok(())
+}
+
+/// Returns the world.
+pub fn get() -> &'static World {
+    unsafe { WORLD.as_ref().expect("World not initialized") }
+}
+
+/// Returns the position of the chunk containing the spawn location.
+pub fn spawn_chunk() -> ChunkPos {
+    let spawn = config::get().spawn_location.to_block_pos();
+    ChunkPos::new(spawn.x >> 4, spawn.z >> 4)
+}
+
+/// Creates a block entity from its NBT data, stripping the ID and position.
+pub fn block_entity(id: &str, pos: BlockPos, nbt: &NbtCompound) -> Option<BlockEntity> {
+    let Ok(kind) = azalea_registry::BlockEntityKind::from_str(id) else {
+        debug!(id, "Skipping unknown block entity");
+        return None;
+    };
+    let mut data = NbtCompound::new();
+    for (key, value) in nbt.iter() {
+        if !matches!(
+            &*key.to_str(),
+            "id" | "Id" | "x" | "y" | "z" | "Pos" | "keepPacked"
+        ) {
+            data.insert(key.clone(), value.clone());
+        }
+    }
+    Some(BlockEntity {
+        packed_xz: (((pos.x & 15) << 4) | (pos.z & 15)) as u8,
+        y: pos.y as u16,
+        kind,
+        data: Nbt::Some(data.to_nbt()),
+    })
+}
@@ -0,0 +1,446 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read},
+};
+
+use anyhow::Context;
+use azalea_core::position::BlockPos;
+use simdnbt::owned::{Nbt, NbtCompound};
+use tracing::*;
+
+use super::{
+    blocks::{self, BlockResolver},
+    World,
+};
+
+/// Blocks in structure files that mark positions which should be left untouched.
+const STRUCTURE_VOID: &str = "minecraft:structure_void";
+
+/// Pastes a Sponge schematic (v2 or v3) or vanilla structure file into the world,
+/// with its minimum corner at `origin`.
+#[tracing::instrument(skip(world, resolver))]
+pub fn paste(
+    world: &mut World,
+    path: &str,
+    origin: BlockPos,
+    resolver: &BlockResolver,
+) -> anyhow::Result<()> {
+    debug!("Pasting schematic into world");
+    let data = std::fs::read(path).context(format!("Failed to read schematic {path}"))?;
+    let data = decompress(data).context("Failed to decompress schematic")?;
+    let Nbt::Some(nbt) = Nbt::read(&mut Cursor::new(&data[..]))? else {
+        anyhow::bail!("Schematic {path} is empty");
+    };
+    paste_nbt(world, &nbt, origin, resolver).context(format!("Failed to paste schematic {path}"))
+}
+
+/// Pastes the NBT data of a schematic or structure file into the world.
+fn paste_nbt(
+    world: &mut World,
+    nbt: &NbtCompound,
+    origin: BlockPos,
+    resolver: &BlockResolver,
+) -> anyhow::Result<()> {
+    let mut paster = Paster {
+        world,
+        resolver,
+        origin,
+        unknown_blocks: HashSet::new(),
+        clipped_blocks: 0,
+    };
+    if let Some(schematic) = nbt.compound("Schematic") {
+        // Sponge v3 wraps everything in a `Schematic` compound
+        paster.paste_sponge(schematic)?;
+    } else if nbt.int("Version").is_some() {
+        paster.paste_sponge(nbt)?;
+    } else if nbt.list("blocks").is_some() {
+        paster.paste_structure(nbt)?;
+    } else {
+        anyhow::bail!("Unrecognized schematic format");
+    }
+
+    if !paster.unknown_blocks.is_empty() {
+        warn!(blocks = ?paster.unknown_blocks, "Unknown blocks were replaced with air");
+    }
+    if paster.clipped_blocks > 0 {
+        warn!(
+            blocks = paster.clipped_blocks,
+            "Schematic extends beyond the world radius, blocks outside of it were left out"
+        );
+    }
+    Ok(())
+}
+
+/// Both schematic formats are usually gzip-compressed, but may also be stored as-is.
+fn decompress(data: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    if !data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(data);
+    }
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+struct Paster<'a> {
+    world: &'a mut World,
+    resolver: &'a BlockResolver,
+    origin: BlockPos,
+    /// Block names that could not be resolved
+    unknown_blocks: HashSet<String>,
+    /// Number of blocks left out because they are beyond the world radius
+    clipped_blocks: usize,
+}
+
+impl Paster<'_> {
+    /// Returns the world position of a position relative to the schematic.
+    fn offset(&self, x: i32, y: i32, z: i32) -> BlockPos {
+        BlockPos::new(self.origin.x + x, self.origin.y + y, self.origin.z + z)
+    }
+
+    /// Places a block, counting it if it is beyond the world radius.
+    fn set_block(&mut self, pos: BlockPos, state: u32) {
+        if !self.world.set_block(pos, state, self.resolver) {
+            self.clipped_blocks += 1;
+        }
+    }
+
+    /// Resolves a block state, remembering it if it is unknown.
+    fn resolve(&mut self, name: &str, state: Option<u32>) -> u32 {
+        state.unwrap_or_else(|| {
+            self.unknown_blocks.insert(name.to_string());
+            azalea_block::BlockState::AIR.id
+        })
+    }
+
+    fn paste_sponge(&mut self, root: &NbtCompound) -> anyhow::Result<()> {
+        let version = root.int("Version").unwrap_or(2);
+        let width = root.short("Width").context("Missing schematic width")? as u16 as usize;
+        let height = root.short("Height").context("Missing schematic height")? as u16 as usize;
+        let length = root.short("Length").context("Missing schematic length")? as u16 as usize;
+        trace!(version, width, height, length, "Pasting Sponge schematic");
+
+        // v3 moved the palette, block data and block entities into a `Blocks` container
+        let (blocks, data, block_entities) = if version >= 3 {
+            let blocks = root
+                .compound("Blocks")
+                .context("Missing schematic blocks")?;
+            (
+                blocks,
+                blocks.byte_array("Data"),
+                blocks.list("BlockEntities"),
+            )
+        } else {
+            (
+                root,
+                root.byte_array("BlockData"),
+                root.list("BlockEntities"),
+            )
+        };
+        let data = data.context("Missing schematic block data")?;
+
+        let mut palette = HashMap::new();
+        for (name, index) in blocks
+            .compound("Palette")
+            .context("Missing schematic palette")?
+            .iter()
+        {
+            let Some(index) = index.int() else {
+                continue;
+            };
+            let name = name.to_str();
+            let state = self.resolver.resolve_str(&name);
+            palette.insert(index as u32, self.resolve(&name, state));
+        }
+
+        // Block data is a list of palette indices, encoded as VarInts in XZY order
+        let mut bytes = data.iter();
+        for index in 0..width * height * length {
+            let mut value = 0u32;
+            let mut shift = 0;
+            loop {
+                let byte = *bytes.next().context("Truncated schematic block data")?;
+                value |= ((byte & 0x7F) as u32) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                shift += 7;
+                // A VarInt is at most 5 bytes long, any further byte would overflow the value
+                if shift >= 35 {
+                    anyhow::bail!("Schematic block data contains a VarInt longer than 5 bytes");
+                }
+            }
+
+            let x = index % width;
+            let z = (index / width) % length;
+            let y = index / (width * length);
+            let state = palette
+                .get(&value)
+                .copied()
+                .unwrap_or(azalea_block::BlockState::AIR.id);
+            let pos = self.offset(x as i32, y as i32, z as i32);
+            self.set_block(pos, state);
+        }
+
+        for entity in block_entities
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+        {
+            let (Some(&[x, y, z]), Some(id)) = (entity.int_array("Pos"), entity.string("Id"))
+            else {
+                continue;
+            };
+            // v2 stores the block entity data inline, v3 in a separate `Data` compound
+            let data = entity.compound("Data").unwrap_or(entity);
+            let pos = self.offset(x, y, z);
+            if let Some(entity) = super::block_entity(&id.to_str(), pos, data) {
+                self.world.set_block_entity(pos, entity);
+            }
+        }
+        Ok(())
+    }
+
+    fn paste_structure(&mut self, root: &NbtCompound) -> anyhow::Result<()> {
+        trace!("Pasting structure");
+        // Structures with multiple palettes (e.g. shipwrecks) store them in `palettes`
+        let palette = root
+            .list("palette")
+            .and_then(|list| list.compounds())
+            .or_else(|| {
+                root.list("palettes")
+                    .and_then(|list| list.lists())
+                    .and_then(|lists| lists.first())
+                    .and_then(|list| list.compounds())
+            })
+            .context("Missing structure palette")?;
+        let palette: Vec<Option<u32>> = palette
+            .iter()
+            .map(|entry| {
+                let (name, properties) = blocks::read_palette_entry(entry);
+                if name == STRUCTURE_VOID {
+                    return None;
+                }
+                let state = self.resolver.resolve(&name, &properties);
+                Some(self.resolve(&name, state))
+            })
+            .collect();
+
+        for block in root
+            .list("blocks")
+            .and_then(|list| list.compounds())
+            .unwrap_or_default()
+        {
+            let (Some(&[x, y, z]), Some(state)) = (
+                block.list("pos").and_then(|list| list.ints()),
+                block.int("state"),
+            ) else {
+                continue;
+            };
+            let Some(state) = palette.get(state as usize).copied().flatten() else {
+                continue;
+            };
+            let pos = self.offset(x, y, z);
+            self.set_block(pos, state);
+
+            if let Some(nbt) = block.compound("nbt") {
+                let entity = nbt
+                    .string("id")
+                    .and_then(|id| super::block_entity(&id.to_str(), pos, nbt));
+                if let Some(entity) = entity {
+                    self.world.set_block_entity(pos, entity);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use azalea_core::position::ChunkPos;
+    use simdnbt::owned::{NbtList, NbtTag};
+
+    use super::*;
+    use crate::world::Dimension;
+
+    /// Creates a world with a single loaded chunk at (0, 0), 16 blocks high.
+    fn world() -> World {
+        let dimension = Dimension {
+            min_y: 0,
+            height: 16,
+            has_skylight: true,
+        };
+        let mut world = World::new(dimension, 0);
+        let chunk = world.empty_chunk();
+        world.insert_chunk(ChunkPos::new(0, 0), chunk);
+        world
+    }
+
+    fn compound(entries: Vec<(&str, NbtTag)>) -> NbtCompound {
+        let mut compound = NbtCompound::new();
+        for (name, tag) in entries {
+            compound.insert(name.to_string(), tag);
+        }
+        compound
+    }
+
+    /// A 2x1x1 Sponge palette and block data, with stone at index 128 to need a 2-byte VarInt.
+    fn sponge_blocks() -> (NbtTag, NbtTag) {
+        let palette = compound(vec![
+            ("minecraft:air", NbtTag::Int(0)),
+            ("minecraft:stone", NbtTag::Int(128)),
+        ]);
+        (
+            NbtTag::Compound(palette),
+            NbtTag::ByteArray(vec![0x80, 0x01, 0x00]),
+        )
+    }
+
+    fn size() -> Vec<(&'static str, NbtTag)> {
+        vec![
+            ("Width", NbtTag::Short(2)),
+            ("Height", NbtTag::Short(1)),
+            ("Length", NbtTag::Short(1)),
+        ]
+    }
+
+    fn assert_stone_then_air(world: &World, resolver: &BlockResolver) {
+        let stone = resolver.resolve_str("minecraft:stone").unwrap();
+        let air = azalea_block::BlockState::AIR.id;
+        assert_eq!(world.block_at(BlockPos::new(1, 2, 3)), Some(stone));
+        assert_eq!(world.block_at(BlockPos::new(2, 2, 3)), Some(air));
+    }
+
+    #[test]
+    fn pastes_sponge_v2() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let (palette, data) = sponge_blocks();
+        let mut entries = size();
+        entries.extend([
+            ("Version", NbtTag::Int(2)),
+            ("Palette", palette),
+            ("BlockData", data),
+        ]);
+        let mut world = world();
+        paste_nbt(
+            &mut world,
+            &compound(entries),
+            BlockPos::new(1, 2, 3),
+            &resolver,
+        )
+        .unwrap();
+        assert_stone_then_air(&world, &resolver);
+    }
+
+    #[test]
+    fn pastes_sponge_v3() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let (palette, data) = sponge_blocks();
+        let blocks = compound(vec![("Palette", palette), ("Data", data)]);
+        let mut entries = size();
+        entries.extend([
+            ("Version", NbtTag::Int(3)),
+            ("Blocks", NbtTag::Compound(blocks)),
+        ]);
+        let root = compound(vec![("Schematic", NbtTag::Compound(compound(entries)))]);
+        let mut world = world();
+        paste_nbt(&mut world, &root, BlockPos::new(1, 2, 3), &resolver).unwrap();
+        assert_stone_then_air(&world, &resolver);
+    }
+
+    #[test]
+    fn rejects_overlong_varint() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let (palette, _) = sponge_blocks();
+        let mut entries = size();
+        entries.extend([
+            ("Version", NbtTag::Int(2)),
+            ("Palette", palette),
+            ("BlockData", NbtTag::ByteArray(vec![0xFF; 6])),
+        ]);
+        let result = paste_nbt(
+            &mut world(),
+            &compound(entries),
+            BlockPos::new(0, 0, 0),
+            &resolver,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_block_data() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let (palette, _) = sponge_blocks();
+        let mut entries = size();
+        entries.extend([
+            ("Version", NbtTag::Int(2)),
+            ("Palette", palette),
+            ("BlockData", NbtTag::ByteArray(vec![0x80])),
+        ]);
+        let result = paste_nbt(
+            &mut world(),
+            &compound(entries),
+            BlockPos::new(0, 0, 0),
+            &resolver,
+        );
+        assert!(result.is_err());
+    }
+
+    fn structure_block(pos: [i32; 3], state: i32) -> NbtCompound {
+        compound(vec![
+            ("pos", NbtTag::List(NbtList::Int(pos.to_vec()))),
+            ("state", NbtTag::Int(state)),
+        ])
+    }
+
+    fn structure_palette() -> NbtList {
+        let entry = |name: &str| compound(vec![("Name", NbtTag::String(name.into()))]);
+        NbtList::Compound(vec![entry("minecraft:stone"), entry(STRUCTURE_VOID)])
+    }
+
+    fn structure_blocks() -> NbtTag {
+        NbtTag::List(NbtList::Compound(vec![
+            structure_block([0, 0, 0], 0),
+            structure_block([1, 0, 0], 1),
+            // Beyond the loaded chunk, so it is clipped
+            structure_block([20, 0, 0], 0),
+        ]))
+    }
+
+    #[test]
+    fn pastes_structure() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let root = compound(vec![
+            ("palette", NbtTag::List(structure_palette())),
+            ("blocks", structure_blocks()),
+        ]);
+        let mut world = world();
+        let stone = resolver.resolve_str("minecraft:stone").unwrap();
+        // Structure voids leave the existing block untouched
+        world.set_block(BlockPos::new(1, 0, 0), stone, &resolver);
+        paste_nbt(&mut world, &root, BlockPos::new(0, 0, 0), &resolver).unwrap();
+        assert_eq!(world.block_at(BlockPos::new(0, 0, 0)), Some(stone));
+        assert_eq!(world.block_at(BlockPos::new(1, 0, 0)), Some(stone));
+        assert_eq!(world.block_at(BlockPos::new(20, 0, 0)), None);
+        assert_eq!(world.chunks().count(), 1);
+    }
+
+    #[test]
+    fn pastes_structure_with_multiple_palettes() {
+        let resolver = BlockResolver::load(None).unwrap();
+        let root = compound(vec![
+            (
+                "palettes",
+                NbtTag::List(NbtList::List(vec![structure_palette()])),
+            ),
+            ("blocks", structure_blocks()),
+        ]);
+        let mut world = world();
+        paste_nbt(&mut world, &root, BlockPos::new(0, 0, 0), &resolver).unwrap();
+        let stone = resolver.resolve_str("minecraft:stone").unwrap();
+        assert_eq!(world.block_at(BlockPos::new(0, 0, 0)), Some(stone));
+        assert_eq!(
+            world.block_at(BlockPos::new(1, 0, 0)),
+            Some(azalea_block::BlockState::AIR.id)
+        );
+    }
+}
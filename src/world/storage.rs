@@ -0,0 +1,75 @@
+/// Unpacks `count` values of `bits` bits each from a long array.
+/// Since 1.16, values never span across two longs, so any leftover bits are padding.
+pub fn unpack(data: &[i64], bits: usize, count: usize) -> Vec<u32> {
+    let per_long = 64 / bits;
+    let mask = (1u64 << bits) - 1;
+    (0..count)
+        .map(|i| {
+            let long = data.get(i / per_long).copied().unwrap_or_default() as u64;
+            ((long >> ((i % per_long) * bits)) & mask) as u32
+        })
+        .collect()
+}
+
+/// Returns the amount of bits needed to index a palette of the given length.
+pub fn palette_bits(len: usize) -> usize {
+    (usize::BITS - len.saturating_sub(1).leading_zeros()) as usize
+}
+
+/// Packs values of `bits` bits each into a long array, the inverse of [`unpack`].
+pub fn pack(values: &[u32], bits: usize) -> Vec<u64> {
+    let per_long = 64 / bits;
+    values
+        .chunks(per_long)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |long, (i, value)| long | (*value as u64) << (i * bits))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_bits_cover_every_index() {
+        assert_eq!(palette_bits(0), 0);
+        assert_eq!(palette_bits(1), 0);
+        assert_eq!(palette_bits(2), 1);
+        assert_eq!(palette_bits(3), 2);
+        assert_eq!(palette_bits(4), 2);
+        assert_eq!(palette_bits(5), 3);
+        assert_eq!(palette_bits(16), 4);
+        assert_eq!(palette_bits(17), 5);
+        assert_eq!(palette_bits(4096), 12);
+    }
+
+    #[test]
+    fn unpack_reverses_pack() {
+        for bits in [1, 4, 5, 7, 15] {
+            let values: Vec<u32> = (0..4096).map(|i| (i * 31 % (1 << bits)) as u32).collect();
+            let packed: Vec<i64> = pack(&values, bits).into_iter().map(|l| l as i64).collect();
+            assert_eq!(unpack(&packed, bits, values.len()), values, "{bits} bits");
+        }
+    }
+
+    #[test]
+    fn values_never_span_two_longs() {
+        // 12 values of 5 bits fit in a long, leaving 4 bits of padding
+        let values = [0b11111; 13];
+        let packed = pack(&values, 5);
+        assert_eq!(packed.len(), 2);
+        assert_eq!(packed[0] >> 60, 0);
+        assert_eq!(packed[0], (1 << 60) - 1);
+        assert_eq!(packed[1], 0b11111);
+    }
+
+    #[test]
+    fn missing_longs_unpack_as_zero() {
+        assert_eq!(unpack(&[0x21], 4, 4), vec![1, 2, 0, 0]);
+        assert_eq!(unpack(&[], 4, 2), vec![0, 0]);
+    }
+}